@@ -0,0 +1,178 @@
+use std::marker::PhantomData;
+
+use rand::Rng;
+
+use crate::{
+    termination::Termination, ImprovementHeuristic, Move, MoveNeighborhood, Problem, Ring, ToF64,
+};
+
+/// Decides which moves `AcceptanceSearch` applies.
+pub trait AcceptanceCriterion<P: Problem> {
+    /// Prepares the criterion for a search from a solution with the given score.
+    fn start(&mut self, score: P::Score);
+
+    /// Whether the search moves from a solution with score `current` to one with score
+    /// `candidate`. Called once per iteration.
+    fn accepts(&mut self, current: P::Score, candidate: P::Score) -> bool;
+}
+
+/// Late acceptance hill climbing (Burke and Bykov, 2017): a candidate is accepted if it is not
+/// worse than the current solution or than the current solution `length` iterations ago.
+pub struct LateAcceptance<S> {
+    length: usize,
+    history: Vec<S>,
+    iteration: usize,
+}
+
+impl<S> LateAcceptance<S> {
+    pub fn new(length: usize) -> Self {
+        Self {
+            length,
+            history: Vec::new(),
+            iteration: 0,
+        }
+    }
+}
+
+impl<P: Problem> AcceptanceCriterion<P> for LateAcceptance<P::Score>
+where
+    P::Score: Copy,
+{
+    fn start(&mut self, score: P::Score) {
+        self.history = vec![score; self.length.max(1)];
+        self.iteration = 0;
+    }
+
+    fn accepts(&mut self, current: P::Score, candidate: P::Score) -> bool {
+        let k = self.iteration % self.history.len();
+        let accepted = candidate >= current || candidate >= self.history[k];
+        self.history[k] = if accepted { candidate } else { current };
+        self.iteration += 1;
+        accepted
+    }
+}
+
+/// Threshold accepting (Dueck and Scheuer, 1990): a candidate is accepted if it is worse than the
+/// current solution by at most the threshold. The threshold starts at `start` times the absolute
+/// initial score and is multiplied by `decay` in every iteration.
+pub struct ThresholdAccepting {
+    start: f64,
+    decay: f64,
+    threshold: f64,
+}
+
+impl ThresholdAccepting {
+    pub fn new(start: f64, decay: f64) -> Self {
+        Self {
+            start,
+            decay,
+            threshold: 0.0,
+        }
+    }
+}
+
+impl<P: Problem> AcceptanceCriterion<P> for ThresholdAccepting
+where
+    P::Score: Ring + ToF64,
+{
+    fn start(&mut self, score: P::Score) {
+        self.threshold = self.start * score.to_f64().abs();
+    }
+
+    fn accepts(&mut self, current: P::Score, candidate: P::Score) -> bool {
+        let accepted = (current - candidate).to_f64() <= self.threshold;
+        self.threshold *= self.decay;
+        accepted
+    }
+}
+
+/// The great deluge algorithm (Dueck, 1993): a candidate is accepted if it is not worse than the
+/// current solution or its score is above the water level. The level starts at the initial score
+/// and rises by `rain_speed` times its absolute value in every iteration, so that it approaches
+/// good scores quickly from poor initial solutions and slowly near them.
+pub struct GreatDeluge {
+    rain_speed: f64,
+    level: f64,
+}
+
+impl GreatDeluge {
+    pub fn new(rain_speed: f64) -> Self {
+        Self {
+            rain_speed,
+            level: 0.0,
+        }
+    }
+}
+
+impl<P: Problem> AcceptanceCriterion<P> for GreatDeluge
+where
+    P::Score: Ring + ToF64,
+{
+    fn start(&mut self, score: P::Score) {
+        self.level = score.to_f64();
+    }
+
+    fn accepts(&mut self, current: P::Score, candidate: P::Score) -> bool {
+        let accepted = candidate >= current || candidate.to_f64() >= self.level;
+        self.level += self.rain_speed * self.level.abs();
+        accepted
+    }
+}
+
+/// Draws random moves of `N` (see `MoveNeighborhood::random_move`) and applies those the
+/// acceptance criterion accepts, with one iteration per drawn move. The search only stops early
+/// if the neighborhood has no moves. The best solution found is returned.
+pub struct AcceptanceSearch<N, A, G> {
+    termination: Termination,
+    criterion: A,
+    rng: G,
+    _n: PhantomData<N>,
+}
+
+impl<N, A, G: Rng> AcceptanceSearch<N, A, G> {
+    pub fn new(termination: Termination, criterion: A, rng: G) -> Self {
+        Self {
+            termination,
+            criterion,
+            rng,
+            _n: PhantomData,
+        }
+    }
+}
+
+impl<P, N, A, G> ImprovementHeuristic<P> for AcceptanceSearch<N, A, G>
+where
+    P: Problem,
+    P::Solution: Clone,
+    P::Score: Ring,
+    N: MoveNeighborhood<P>,
+    A: AcceptanceCriterion<P>,
+    G: Rng,
+{
+    fn improve(&mut self, instance: &P, current: P::Solution) -> P::Solution {
+        let mut best = current.clone();
+        let mut current = current;
+        self.criterion.start(P::score(&current));
+
+        while !self.termination.should_terminate() {
+            let current_score = P::score(&current);
+            let Some(m) = N::random_move(instance, &current, &mut self.rng) else {
+                break;
+            };
+            self.termination.iteration();
+            if !self
+                .criterion
+                .accepts(current_score, current_score + m.score_increase())
+            {
+                continue;
+            }
+            current = m.into_solution();
+
+            if P::score(&current) > P::score(&best) {
+                best = current.clone();
+            }
+        }
+
+        best
+    }
+}
@@ -0,0 +1,53 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::PathBuf,
+};
+
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use gtsp::gtsp::{GtspProblem, NoonBean};
+
+/// Transforms GTSP instances into ATSP instances for external solvers and maps their tours back
+#[derive(Debug, Parser)]
+struct Args {
+    /// Path of the GTSP instance
+    instance: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Write the transformed instance in the TSPLIB ATSP format to the terminal
+    Export,
+    /// Read an ATSP tour in the TSPLIB format and write the GTSP solution to the terminal
+    Import {
+        /// Path of the ATSP tour
+        tour: PathBuf,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let problem = GtspProblem::<i64>::read_from_text(BufReader::new(File::open(&args.instance)?))?;
+    let atsp = NoonBean::new(&problem);
+
+    match args.command {
+        Command::Export => {
+            let name = args
+                .instance
+                .file_stem()
+                .context("instance has no file name")?
+                .to_string_lossy();
+            atsp.write_tsplib(BufWriter::new(io::stdout().lock()), &name)?;
+        }
+        Command::Import { tour } => {
+            let solution = atsp.read_tsplib_tour(&problem, BufReader::new(File::open(tour)?))?;
+            eprintln!("GTSP weight: {}", solution.weight());
+            solution.write_to_text(io::stdout().lock())?;
+        }
+    }
+
+    Ok(())
+}
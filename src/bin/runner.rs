@@ -1,26 +1,157 @@
 use std::{
-    env,
+    fmt::{self, Display},
     fs::File,
     io::{self, BufReader},
+    str::FromStr,
     time::{Duration, Instant},
 };
 
+use anyhow::{bail, Context as _};
+use clap::{Parser, ValueEnum};
 use gtsp::{
+    acceptance::{AcceptanceSearch, GreatDeluge, LateAcceptance, ThresholdAccepting},
     chain::Chain,
-    cycle_neighborhoods::{Cycle, ExploreOnce},
+    cycle_neighborhoods::{Cycle, ExploreOnce, OnExhaustion},
     gtsp::{
-        neighborhoods::{InsertsNeighborhood, SwapNeighborhood, TwoOptNeighborhood},
-        ClusterOptimization, GtspProblem, RandomSolution,
+        neighborhoods::{
+            Candidates, ClusterSwapNeighborhood, InsertsNeighborhood, Or3OptNeighborhood,
+            OrOptNeighborhood, SwapNeighborhood, ThreeOptNeighborhood, TwoOptNeighborhood,
+        },
+        Alns, AntColony, CheapestInsertion, ClusterOptimization, DontLookLocalSearch, DoubleBridge,
+        FarthestInsertion, GtspProblem, GuidedLocalSearch, LinKernighan, NearestNeighbor,
+        PathRelinking, RandomSolution, WarmStart, WithWindowCo,
     },
-    localsearch::LocalSearch,
+    localsearch::{LocalSearch, Pivoting},
     multistart::Multistart,
-    tabusearch::TabuSearch,
+    tabusearch::{TabuSearch, Tenure},
     termination::Termination,
-    AsMoveNeighborhood, ImproveInitial, ImprovementHeuristic, MetaHeuristic,
+    ImproveInitial, ImprovementHeuristic, InitialSolution, MetaHeuristic,
 };
 use rand::{rngs::SmallRng, SeedableRng as _};
 use serde_derive::Serialize;
 
+#[derive(Debug, Parser)]
+struct Args {
+    /// How the initial solutions of all heuristics are constructed
+    #[arg(long, value_enum, default_value_t = Initial::Random)]
+    initial: Initial,
+    /// Apply Cluster Optimization to every initial solution
+    #[arg(long)]
+    initial_co: bool,
+    /// Which improving move local searches apply in every iteration
+    #[arg(long, value_enum, default_value_t = PivotingRule::Best)]
+    pivoting: PivotingRule,
+    /// Tabu tenures to run tabu search with: a number `L`, a range `MIN-MAX` to draw the tenure
+    /// from at random, or `reactive:MIN-MAX` for a reactive tenure within the range
+    #[arg(long, default_values = ["100", "500"])]
+    tabu_tenure: Vec<TenureSpec>,
+    /// Restrict 2-opt, swap, inserts and Or-opt moves to nearby clusters
+    #[arg(long)]
+    candidates: bool,
+    /// Tour to start from with `--initial warm-start` (required), given once per instance in the
+    /// same order
+    #[arg(long)]
+    warm_start: Vec<String>,
+    /// Paths of the instances to run the experiment on
+    instances: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Initial {
+    Random,
+    NearestNeighbor,
+    CheapestInsertion,
+    FarthestInsertion,
+    WarmStart,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PivotingRule {
+    Best,
+    First,
+    RandomizedFirst,
+}
+
+#[derive(Debug, Clone)]
+enum TenureSpec {
+    Fixed(usize),
+    Randomized(usize, usize),
+    Reactive(usize, usize),
+}
+
+impl FromStr for TenureSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = |r: &str| -> anyhow::Result<(usize, usize)> {
+            let (min, max) = r.split_once('-').context("expected MIN-MAX")?;
+            let (min, max) = (min.parse()?, max.parse()?);
+            if min > max {
+                bail!("empty range {r}");
+            }
+            Ok((min, max))
+        };
+        if let Some(r) = s.strip_prefix("reactive:") {
+            let (min, max) = range(r)?;
+            Ok(Self::Reactive(min, max))
+        } else if s.contains('-') {
+            let (min, max) = range(s)?;
+            Ok(Self::Randomized(min, max))
+        } else {
+            Ok(Self::Fixed(s.parse()?))
+        }
+    }
+}
+
+impl Display for TenureSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed(l) => write!(f, "{l}"),
+            Self::Randomized(min, max) => write!(f, "{min}-{max}"),
+            Self::Reactive(min, max) => write!(f, "reactive {min}-{max}"),
+        }
+    }
+}
+
+fn make_tenure(spec: &TenureSpec, rng: &mut SmallRng) -> Tenure {
+    match *spec {
+        TenureSpec::Fixed(l) => Tenure::fixed(l),
+        TenureSpec::Randomized(min, max) => {
+            Tenure::randomized(min, max, SmallRng::from_rng(rng).unwrap())
+        }
+        TenureSpec::Reactive(min, max) => Tenure::reactive(min, max),
+    }
+}
+
+fn make_pivoting(args: &Args, rng: &mut SmallRng) -> Pivoting {
+    match args.pivoting {
+        PivotingRule::Best => Pivoting::BestImprovement,
+        PivotingRule::First => Pivoting::FirstImprovement,
+        PivotingRule::RandomizedFirst => {
+            Pivoting::RandomizedFirstImprovement(SmallRng::from_rng(rng).unwrap())
+        }
+    }
+}
+
+fn make_initial(
+    args: &Args,
+    warm_start: Option<&WarmStart<i64>>,
+    rng: &mut SmallRng,
+) -> Box<dyn InitialSolution<GtspProblem<i64>>> {
+    let initial: Box<dyn InitialSolution<_>> = match args.initial {
+        Initial::Random => Box::new(RandomSolution::new(SmallRng::from_rng(rng).unwrap())),
+        Initial::NearestNeighbor => Box::new(NearestNeighbor),
+        Initial::CheapestInsertion => Box::new(CheapestInsertion),
+        Initial::FarthestInsertion => Box::new(FarthestInsertion),
+        Initial::WarmStart => Box::new(warm_start.expect("no warm start tour").clone()),
+    };
+    if args.initial_co {
+        Box::new(ImproveInitial::new(initial, ClusterOptimization))
+    } else {
+        initial
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct Run<'a> {
     problem: &'a str,
@@ -29,17 +160,32 @@ struct Run<'a> {
 }
 
 fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if matches!(args.initial, Initial::WarmStart) && args.warm_start.len() != args.instances.len() {
+        bail!("--initial warm-start needs exactly one --warm-start tour per instance");
+    }
+    if !matches!(args.initial, Initial::WarmStart) && !args.warm_start.is_empty() {
+        bail!("--warm-start tours are only used with --initial warm-start");
+    }
     let mut rng = SmallRng::seed_from_u64(42);
     let mut writer = csv::Writer::from_writer(io::stdout().lock());
 
-    for path in env::args().skip(1) {
-        let problem = GtspProblem::<i64>::read_from_text(BufReader::new(File::open(&path)?))?;
+    for (i, path) in args.instances.iter().enumerate() {
+        let problem = GtspProblem::<i64>::read_from_text(BufReader::new(File::open(path)?))?;
+        let warm_start = args
+            .warm_start
+            .get(i)
+            .map(|tour_path| {
+                WarmStart::read_from_text(&problem, BufReader::new(File::open(tour_path)?))
+                    .with_context(|| format!("read warm start tour {tour_path}"))
+            })
+            .transpose()?;
         let suffix = if problem.is_symmetric() {
             ""
         } else {
             " (asymm.)"
         };
-        let problem_name = path + suffix;
+        let problem_name = path.to_owned() + suffix;
         eprintln!("Problem: {problem_name}");
 
         let d = Duration::from_secs(3);
@@ -69,12 +215,15 @@ fn main() -> anyhow::Result<()> {
             };
         }
 
-        macro_rules! of_random {
+        macro_rules! of_initial {
             ($im: expr) => {
-                ImproveInitial::new(
-                    RandomSolution::new(SmallRng::from_rng(&mut rng).unwrap()),
-                    $im,
-                )
+                ImproveInitial::new(make_initial(&args, warm_start.as_ref(), &mut rng), $im)
+            };
+        }
+
+        macro_rules! local_search {
+            ($neigh: ty, $t: expr) => {
+                LocalSearch::<$neigh>::with_pivoting($t, make_pivoting(&args, &mut rng))
             };
         }
 
@@ -89,31 +238,53 @@ fn main() -> anyhow::Result<()> {
                 let t = Termination::after_duration(d);
                 run!(
                     concat!("MS LS ", $name),
-                    Multistart::new(t, || of_random!(LocalSearch::<$neigh>::new(t)))
+                    Multistart::new(t, || of_initial!(local_search!($neigh, t)))
                 )?;
                 let t = Termination::after_duration(d);
                 run!(
                     concat!("MS LS ", $name, " with CO"),
-                    Multistart::new(t, || of_random!(with_co!(LocalSearch::<$neigh>::new(t))))
+                    Multistart::new(t, || of_initial!(with_co!(local_search!($neigh, t))))
+                )?;
+                let t = Termination::after_duration(d);
+                run!(
+                    concat!("MS LS ", $name, " with window CO"),
+                    Multistart::new(t, || of_initial!(local_search!(WithWindowCo<$neigh>, t)))
                 )?;
             };
         }
         macro_rules! run_tabu {
             ($name: expr, $neigh: ty) => {
-                run_tabu!($name, $neigh, 100);
-                run_tabu!($name, $neigh, 500);
+                for spec in &args.tabu_tenure {
+                    run!(
+                        &format!("Tabu {} (L={spec})", $name),
+                        of_initial!(TabuSearch::<$neigh>::new(
+                            Termination::after_duration(d),
+                            make_tenure(spec, &mut rng)
+                        ))
+                    )?;
+                    run!(
+                        &format!("Tabu {} (L={spec}) with CO", $name),
+                        of_initial!(with_co!(TabuSearch::<$neigh>::new(
+                            Termination::after_duration(d),
+                            make_tenure(spec, &mut rng)
+                        )))
+                    )?;
+                }
             };
-            ($name: expr, $neigh: ty, $len: expr) => {
+        }
+
+        macro_rules! run_dont_look {
+            ($name: expr, $neigh: ty) => {
+                let t = Termination::after_duration(d);
                 run!(
-                    concat!("Tabu ", $name, " (L=", $len, ")"),
-                    of_random!(TabuSearch::<$neigh, $len>::new(
-                        Termination::after_duration(d)
-                    ))
+                    concat!("MS DLB LS ", $name),
+                    Multistart::new(t, || of_initial!(DontLookLocalSearch::<$neigh>::new(t)))
                 )?;
+                let t = Termination::after_duration(d);
                 run!(
-                    concat!("Tabu ", $name, " (L=", $len, ") with CO"),
-                    of_random!(with_co!(TabuSearch::<$neigh, $len>::new(
-                        Termination::after_duration(d)
+                    concat!("MS DLB LS ", $name, " with CO"),
+                    Multistart::new(t, || of_initial!(with_co!(
+                        DontLookLocalSearch::<$neigh>::new(t)
                     )))
                 )?;
             };
@@ -130,18 +301,101 @@ fn main() -> anyhow::Result<()> {
         }
 
         for _ in 0..10 {
-            run_all!("2-Opt", TwoOptNeighborhood);
-            run_all!("Swap", SwapNeighborhood);
-            run_all!(
-                "Inserts",
-                AsMoveNeighborhood<InsertsNeighborhood>,
-                InsertsNeighborhood
-            );
+            if args.candidates {
+                run_all!("2-Opt", Candidates<TwoOptNeighborhood>);
+                run_all!("Swap", Candidates<SwapNeighborhood>);
+                run_all!("Cluster Swap", Candidates<ClusterSwapNeighborhood>);
+                run_all!("Inserts", Candidates<InsertsNeighborhood>);
+                run_all!("Or-Opt", Candidates<OrOptNeighborhood>);
+            } else {
+                run_all!("2-Opt", TwoOptNeighborhood);
+                run_all!("Swap", SwapNeighborhood);
+                run_all!("Cluster Swap", ClusterSwapNeighborhood);
+                run_all!("Inserts", InsertsNeighborhood);
+                run_all!("Or-Opt", OrOptNeighborhood);
+            }
+            run_dont_look!("2-Opt", TwoOptNeighborhood);
+            run_dont_look!("Swap", SwapNeighborhood);
+            run_dont_look!("Inserts", InsertsNeighborhood);
+            run_all!("3-Opt", ThreeOptNeighborhood);
+            run_all!("Or-3opt", Or3OptNeighborhood);
+            let t = Termination::after_duration(d);
+            run!(
+                "ALNS",
+                of_initial!(Alns::new(t, SmallRng::from_rng(&mut rng).unwrap()))
+            )?;
+            let t = Termination::after_duration(d);
+            run!(
+                "LAHC Inserts",
+                of_initial!(AcceptanceSearch::<InsertsNeighborhood, _, _>::new(
+                    t,
+                    LateAcceptance::new(200),
+                    SmallRng::from_rng(&mut rng).unwrap()
+                ))
+            )?;
+            let t = Termination::after_duration(d);
+            run!(
+                "Threshold Accepting Inserts",
+                of_initial!(AcceptanceSearch::<InsertsNeighborhood, _, _>::new(
+                    t,
+                    ThresholdAccepting::new(0.003, 0.99999),
+                    SmallRng::from_rng(&mut rng).unwrap()
+                ))
+            )?;
+            let t = Termination::after_duration(d);
+            run!(
+                "Great Deluge Inserts",
+                of_initial!(AcceptanceSearch::<InsertsNeighborhood, _, _>::new(
+                    t,
+                    GreatDeluge::new(1e-4),
+                    SmallRng::from_rng(&mut rng).unwrap()
+                ))
+            )?;
+            let t = Termination::after_duration(d);
+            run!(
+                "GLS 2-Opt",
+                of_initial!(GuidedLocalSearch::new(
+                    t,
+                    local_search!(TwoOptNeighborhood, t)
+                ))
+            )?;
+            let t = Termination::after_duration(d);
+            run!(
+                "GLS Inserts",
+                of_initial!(GuidedLocalSearch::new(
+                    t,
+                    local_search!(InsertsNeighborhood, t)
+                ))
+            )?;
+            let t = Termination::after_duration(d);
+            run!(
+                "ACO",
+                AntColony::new(t, SmallRng::from_rng(&mut rng).unwrap())
+            )?;
+            let t = Termination::after_duration(d);
+            run!(
+                "ACO with DLB 2-Opt and CO",
+                AntColony::with_local_search(
+                    t,
+                    SmallRng::from_rng(&mut rng).unwrap(),
+                    with_co!(DontLookLocalSearch::<TwoOptNeighborhood>::new(t))
+                )
+            )?;
+            let t = Termination::after_duration(d);
+            run!(
+                "MS LK",
+                Multistart::new(t, || of_initial!(LinKernighan::new(t)))
+            )?;
+            let t = Termination::after_duration(d);
+            run!(
+                "MS LK with CO",
+                Multistart::new(t, || of_initial!(with_co!(LinKernighan::new(t))))
+            )?;
             let t = Termination::after_duration(d);
             run!(
                 "MS Cycle",
                 Multistart::new(t, || {
-                    of_random!(Cycle::new(
+                    of_initial!(Cycle::new(
                         [
                             Box::new(ExploreOnce(TwoOptNeighborhood))
                                 as Box<dyn ImprovementHeuristic<GtspProblem<_>>>,
@@ -156,7 +410,7 @@ fn main() -> anyhow::Result<()> {
             run!(
                 "MS Cycle with CO",
                 Multistart::new(t, || {
-                    of_random!(with_co!(Cycle::new(
+                    of_initial!(with_co!(Cycle::new(
                         [
                             Box::new(ExploreOnce(TwoOptNeighborhood))
                                 as Box<dyn ImprovementHeuristic<GtspProblem<_>>>,
@@ -167,6 +421,44 @@ fn main() -> anyhow::Result<()> {
                     )))
                 })
             )?;
+            let t = Termination::after_duration(d);
+            let path_relinking =
+                PathRelinking::new(t, with_co!(local_search!(InsertsNeighborhood, t)));
+            run!(
+                "MS LS Inserts with CO and path relinking",
+                Multistart::with_post_processing(
+                    Termination::after_duration(d * 2 / 3),
+                    || of_initial!(with_co!(local_search!(InsertsNeighborhood, t))),
+                    path_relinking
+                )
+            )?;
+            for (name, on_exhaustion) in [
+                (
+                    "Cycle with restarts",
+                    OnExhaustion::restart(DoubleBridge::new(SmallRng::from_rng(&mut rng).unwrap())),
+                ),
+                (
+                    "Cycle with adaptive restarts",
+                    OnExhaustion::adaptive_restart(DoubleBridge::new(
+                        SmallRng::from_rng(&mut rng).unwrap(),
+                    )),
+                ),
+            ] {
+                let t = Termination::after_duration(d);
+                run!(
+                    name,
+                    of_initial!(with_co!(Cycle::with_on_exhaustion(
+                        [
+                            Box::new(ExploreOnce(TwoOptNeighborhood))
+                                as Box<dyn ImprovementHeuristic<GtspProblem<_>>>,
+                            Box::new(ExploreOnce(SwapNeighborhood)),
+                            Box::new(ExploreOnce(InsertsNeighborhood)),
+                        ],
+                        t,
+                        on_exhaustion
+                    )))
+                )?;
+            }
         }
     }
 
@@ -1,18 +1,74 @@
-use crate::{termination::Termination, ImprovementHeuristic, Neighborhood, Problem};
+use crate::{termination::Termination, ImprovementHeuristic, Neighborhood, Perturbation, Problem};
 
-pub struct Cycle<P> {
+/// How much the last call of a heuristic counts towards its success rate.
+const SUCCESS_RATE_WEIGHT: f64 = 0.2;
+
+enum OnExhaustionKind<P: Problem> {
+    Stop,
+    Restart(Box<dyn Perturbation<P>>),
+    AdaptiveRestart(Box<dyn Perturbation<P>>),
+}
+
+/// What `Cycle` does once every heuristic has failed to improve the current solution.
+pub struct OnExhaustion<P: Problem> {
+    kind: OnExhaustionKind<P>,
+}
+
+impl<P: Problem> OnExhaustion<P> {
+    /// Return the current solution.
+    pub fn stop() -> Self {
+        Self {
+            kind: OnExhaustionKind::Stop,
+        }
+    }
+
+    /// Perturb the best solution and cycle through all heuristics again, in their original order.
+    pub fn restart(perturbation: impl Perturbation<P> + 'static) -> Self {
+        Self {
+            kind: OnExhaustionKind::Restart(Box::new(perturbation)),
+        }
+    }
+
+    /// Like `restart`, but the heuristics are ordered by their recent success rate, an
+    /// exponential moving average of whether their calls improved the solution.
+    pub fn adaptive_restart(perturbation: impl Perturbation<P> + 'static) -> Self {
+        Self {
+            kind: OnExhaustionKind::AdaptiveRestart(Box::new(perturbation)),
+        }
+    }
+}
+
+/// Applies the heuristics in turn. A heuristic that fails to improve the current solution is
+/// skipped until all heuristics have failed, after which `OnExhaustion` decides how to continue.
+/// The best solution found is returned.
+pub struct Cycle<P: Problem> {
     heuristics: Vec<Box<dyn ImprovementHeuristic<P>>>,
     termination: Termination,
+    on_exhaustion: OnExhaustion<P>,
+    order: Vec<usize>,
+    success_rates: Vec<f64>,
 }
 
-impl<P> Cycle<P> {
+impl<P: Problem> Cycle<P> {
     pub fn new(
         heuristics: impl IntoIterator<Item = Box<dyn ImprovementHeuristic<P>>>,
         termination: Termination,
     ) -> Self {
+        Self::with_on_exhaustion(heuristics, termination, OnExhaustion::stop())
+    }
+
+    pub fn with_on_exhaustion(
+        heuristics: impl IntoIterator<Item = Box<dyn ImprovementHeuristic<P>>>,
+        termination: Termination,
+        on_exhaustion: OnExhaustion<P>,
+    ) -> Self {
+        let heuristics: Vec<_> = heuristics.into_iter().collect();
         Self {
-            heuristics: heuristics.into_iter().collect(),
+            order: (0..heuristics.len()).collect(),
+            success_rates: vec![1.0; heuristics.len()],
+            heuristics,
             termination,
+            on_exhaustion,
         }
     }
 }
@@ -23,21 +79,46 @@ where
     P::Solution: Clone,
 {
     fn improve(&mut self, instance: &P, mut current: P::Solution) -> P::Solution {
+        let mut best = current.clone();
+        let mut remaining = self.order.clone();
         let mut i = 0;
         while !self.termination.should_terminate() && !self.heuristics.is_empty() {
-            i %= self.heuristics.len();
-            let next = self.heuristics[i].improve(instance, current.clone());
+            if remaining.is_empty() {
+                let perturbation = match &mut self.on_exhaustion.kind {
+                    OnExhaustionKind::Stop => break,
+                    OnExhaustionKind::Restart(perturbation) => perturbation,
+                    OnExhaustionKind::AdaptiveRestart(perturbation) => {
+                        let success_rates = &self.success_rates;
+                        self.order
+                            .sort_by(|&a, &b| success_rates[b].total_cmp(&success_rates[a]));
+                        perturbation
+                    }
+                };
+                current = perturbation.perturb(instance, &best);
+                remaining = self.order.clone();
+                i = 0;
+            }
+
+            i %= remaining.len();
+            let h = remaining[i];
+            let next = self.heuristics[h].improve(instance, current.clone());
 
-            if P::score(&next) > P::score(&current) {
+            let improved = P::score(&next) > P::score(&current);
+            self.success_rates[h] = (1.0 - SUCCESS_RATE_WEIGHT) * self.success_rates[h]
+                + SUCCESS_RATE_WEIGHT * f64::from(u8::from(improved));
+            if improved {
                 current = next;
+                if P::score(&current) > P::score(&best) {
+                    best = current.clone();
+                }
                 i += 1;
             } else {
-                self.heuristics.remove(i);
+                remaining.remove(i);
             }
 
             self.termination.iteration();
         }
-        current
+        best
     }
 }
 
@@ -1,4 +1,10 @@
-use std::{fmt::Debug, io::BufRead, str::FromStr};
+use std::{
+    fmt::{Debug, Display},
+    io::{self, BufRead, Write},
+    ops::Add,
+    str::FromStr,
+    sync::Arc,
+};
 
 use anyhow::{bail, Context};
 use itertools::Itertools;
@@ -8,20 +14,67 @@ use crate::{InitialSolution, Problem, Ring};
 
 pub mod neighborhoods;
 
+pub mod alns;
+pub use alns::Alns;
+
+pub mod ant_colony;
+pub use ant_colony::AntColony;
+
 pub mod cluster_optimization;
-pub use cluster_optimization::ClusterOptimization;
+pub use cluster_optimization::{ClusterOptimization, WindowClusterOptimization, WithWindowCo};
+
+pub mod construction;
+pub use construction::{CheapestInsertion, FarthestInsertion, NearestNeighbor};
+
+pub mod dont_look_bits;
+pub use dont_look_bits::DontLookLocalSearch;
+
+pub mod guided_local_search;
+pub use guided_local_search::GuidedLocalSearch;
+
+pub mod dynamic_programming;
+pub use dynamic_programming::DynamicProgramming;
+
+pub mod branch_and_bound;
+pub use branch_and_bound::BranchAndBound;
+
+pub mod lin_kernighan;
+pub use lin_kernighan::LinKernighan;
+
+pub mod noon_bean;
+pub use noon_bean::NoonBean;
+
+pub mod path_relinking;
+pub use path_relinking::PathRelinking;
+
+pub mod perturbation;
+pub use perturbation::DoubleBridge;
+
+pub mod warm_start;
+pub use warm_start::WarmStart;
+
+/// How many nearest vertices of other clusters are kept for every vertex, and how many nearest
+/// clusters for every cluster.
+pub const NEIGHBOR_LIST_SIZE: usize = 10;
 
 pub struct GtspProblem<R> {
     number_of_vertices: usize,
-    clusters: Vec<Vec<usize>>,
+    // The fields behind `Arc` are shared with the penalized views of the problem.
+    clusters: Arc<Vec<Vec<usize>>>,
+    cluster_of: Arc<Vec<usize>>,
     is_symmetric: bool,
     is_triangle: bool,
-    dist: Vec<Vec<R>>,
+    /// The distances including the penalties of a penalized view, see `penalized`. Until the
+    /// first penalty is added, this is the same matrix as `base_dist`.
+    dist: Arc<Vec<Vec<R>>>,
+    base_dist: Arc<Vec<Vec<R>>>,
+    neighbors: Arc<Vec<Vec<usize>>>,
+    nearest_clusters: Arc<Vec<Vec<usize>>>,
 }
 
 impl<R> GtspProblem<R>
 where
-    R: FromStr,
+    R: FromStr + Ord + Copy,
     <R as FromStr>::Err: std::error::Error + Send + Sync + 'static,
 {
     pub fn read_from_text(reader: impl BufRead) -> anyhow::Result<Self> {
@@ -80,12 +133,53 @@ where
             })
             .collect::<anyhow::Result<Vec<_>>>()?;
 
+        let mut cluster_of = vec![usize::MAX; number_of_vertices];
+        for (i, c) in clusters.iter().enumerate() {
+            for &v in c {
+                *cluster_of.get_mut(v).context("vertex out of range")? = i;
+            }
+        }
+
+        let neighbors = (0..number_of_vertices)
+            .map(|u| {
+                (0..number_of_vertices)
+                    .filter(|&v| cluster_of[v] != cluster_of[u])
+                    .sorted_by_key(|&v| dist[u][v])
+                    .take(NEIGHBOR_LIST_SIZE)
+                    .collect()
+            })
+            .collect();
+
+        // The distance between two clusters is the shortest arc between them in either direction.
+        let cluster_dist = |c: &[usize], d: &[usize]| {
+            c.iter()
+                .cartesian_product(d)
+                .map(|(&u, &v)| dist[u][v].min(dist[v][u]))
+                .min()
+        };
+        let nearest_clusters = clusters
+            .iter()
+            .enumerate()
+            .map(|(c, cluster)| {
+                (0..clusters.len())
+                    .filter(|&d| d != c)
+                    .sorted_by_cached_key(|&d| cluster_dist(cluster, &clusters[d]))
+                    .take(NEIGHBOR_LIST_SIZE)
+                    .collect()
+            })
+            .collect();
+
+        let dist = Arc::new(dist);
         Ok(Self {
             number_of_vertices,
-            clusters,
+            clusters: Arc::new(clusters),
+            cluster_of: Arc::new(cluster_of),
             is_symmetric,
             is_triangle,
-            dist,
+            dist: Arc::clone(&dist),
+            base_dist: dist,
+            neighbors: Arc::new(neighbors),
+            nearest_clusters: Arc::new(nearest_clusters),
         })
     }
 }
@@ -98,15 +192,92 @@ impl<R> GtspProblem<R> {
         debug_assert_ne!(u, v);
         self.dist[u][v]
     }
+    /// The distance without the penalties of a penalized view.
+    pub fn base_dist(&self, u: usize, v: usize) -> R
+    where
+        R: Copy,
+    {
+        debug_assert_ne!(u, v);
+        self.base_dist[u][v]
+    }
+    /// A view of the problem whose distances include penalties of arcs, which are added with
+    /// `add_penalty`, for example to run local search on an augmented objective. The view shares
+    /// the clusters and the nearest neighbor lists of the original distances, and copies the
+    /// distance matrix when the first penalty is added.
+    pub fn penalized(&self) -> Self {
+        Self {
+            number_of_vertices: self.number_of_vertices,
+            clusters: Arc::clone(&self.clusters),
+            cluster_of: Arc::clone(&self.cluster_of),
+            is_symmetric: self.is_symmetric,
+            is_triangle: self.is_triangle,
+            dist: Arc::clone(&self.dist),
+            base_dist: Arc::clone(&self.base_dist),
+            neighbors: Arc::clone(&self.neighbors),
+            nearest_clusters: Arc::clone(&self.nearest_clusters),
+        }
+    }
+    /// Increases the penalty of the arc from `u` to `v` of a penalized view. Penalties of
+    /// symmetric problems have to be added for both directions.
+    pub fn add_penalty(&mut self, u: usize, v: usize, penalty: R)
+    where
+        R: Copy + Add<Output = R>,
+    {
+        let d = &mut Arc::make_mut(&mut self.dist)[u][v];
+        *d = *d + penalty;
+        self.is_triangle = false;
+    }
     pub fn number_of_vertices(&self) -> usize {
         self.number_of_vertices
     }
+    pub fn number_of_clusters(&self) -> usize {
+        self.clusters.len()
+    }
+    pub fn clusters(&self) -> &[Vec<usize>] {
+        &self.clusters
+    }
+    pub fn cluster_of(&self, v: usize) -> usize {
+        self.cluster_of[v]
+    }
     pub fn is_symmetric(&self) -> bool {
         self.is_symmetric
     }
+    pub fn is_triangle(&self) -> bool {
+        self.is_triangle
+    }
+    /// The nearest vertices of other clusters, ordered by the distance from `v`.
+    pub fn neighbors(&self, v: usize) -> &[usize] {
+        &self.neighbors[v]
+    }
+    /// The nearest other clusters, ordered by the shortest arc between them and `c`.
+    pub fn nearest_clusters(&self, c: usize) -> &[usize] {
+        &self.nearest_clusters[c]
+    }
+
+    pub fn validate_tour(&self, tour: &[usize]) -> anyhow::Result<()> {
+        if tour.len() != self.clusters.len() {
+            bail!(
+                "tour visits {} vertices, but there are {} clusters",
+                tour.len(),
+                self.clusters.len()
+            );
+        }
+        let mut visited = vec![false; self.clusters.len()];
+        for &v in tour {
+            if v >= self.number_of_vertices {
+                bail!("vertex {} does not exist", v + 1);
+            }
+            let c = self.cluster_of(v);
+            if visited[c] {
+                bail!("cluster {} is visited more than once", c + 1);
+            }
+            visited[c] = true;
+        }
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Solution<R> {
     weight: R,
     tour: Vec<usize>,
@@ -129,6 +300,17 @@ where
     }
 }
 
+impl<R: Display> Solution<R> {
+    pub fn write_to_text(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "{}", self.tour.len())?;
+        writeln!(writer, "{}", self.weight)?;
+        for v in &self.tour {
+            writeln!(writer, "{}", v + 1)?;
+        }
+        Ok(())
+    }
+}
+
 impl<R: Ring> Solution<R> {
     pub fn new(problem: &GtspProblem<R>, tour: Vec<usize>) -> Self {
         let mut this = Self {
@@ -0,0 +1,325 @@
+use std::{cmp::Reverse, f64::consts::LN_2};
+
+use itertools::Itertools as _;
+use rand::{
+    distributions::{Distribution as _, WeightedIndex},
+    seq::{index::sample, SliceRandom as _},
+    Rng,
+};
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    termination::Termination,
+    ImprovementHeuristic, Problem, Ring, ToF64,
+};
+
+pub const DEFAULT_MAX_REMOVED: usize = 30;
+const MIN_REMOVED: usize = 2;
+
+/// How strongly worst and related removal prefer the clusters ranked first, as the exponent of
+/// the uniform random number that picks the rank (Ropke and Pisinger, 2006).
+const WORST_RANDOMNESS: i32 = 3;
+const RELATED_RANDOMNESS: i32 = 6;
+
+/// The operator weights are updated after every segment of this many iterations.
+const SEGMENT_LENGTH: usize = 100;
+const REACTION: f64 = 0.1;
+const NEW_BEST_SCORE: f64 = 33.0;
+const IMPROVED_SCORE: f64 = 9.0;
+const ACCEPTED_SCORE: f64 = 13.0;
+
+/// The start temperature accepts a solution this much worse than the initial one with
+/// probability 1/2.
+const START_WORSENING: f64 = 0.05;
+const COOLING_RATE: f64 = 0.9995;
+
+#[derive(Debug, Clone, Copy)]
+enum Destroy {
+    /// Uniformly random clusters.
+    Random,
+    /// Clusters whose removal saves the most.
+    Worst,
+    /// Clusters whose vertices are close to those of already removed clusters (Shaw removal).
+    Related,
+    /// Consecutive clusters of the tour.
+    Segment,
+}
+
+const DESTROY: [Destroy; 4] = [
+    Destroy::Random,
+    Destroy::Worst,
+    Destroy::Related,
+    Destroy::Segment,
+];
+
+/// The `k` of the regret-k insertions used for repairing. Regret-1 insertion is greedy insertion.
+const REGRET: [usize; 3] = [1, 2, 3];
+
+struct OperatorWeights {
+    weights: Vec<f64>,
+    scores: Vec<f64>,
+    uses: Vec<usize>,
+}
+
+impl OperatorWeights {
+    fn new(operators: usize) -> Self {
+        Self {
+            weights: vec![1.0; operators],
+            scores: vec![0.0; operators],
+            uses: vec![0; operators],
+        }
+    }
+
+    fn choose(&self, rng: &mut impl Rng) -> usize {
+        WeightedIndex::new(&self.weights).unwrap().sample(rng)
+    }
+
+    fn reward(&mut self, operator: usize, score: f64) {
+        self.scores[operator] += score;
+        self.uses[operator] += 1;
+    }
+
+    fn end_segment(&mut self) {
+        for ((weight, score), uses) in self
+            .weights
+            .iter_mut()
+            .zip(&mut self.scores)
+            .zip(&mut self.uses)
+        {
+            if *uses > 0 {
+                *weight = (1.0 - REACTION) * *weight + REACTION * *score / *uses as f64;
+            }
+            (*score, *uses) = (0.0, 0);
+        }
+    }
+}
+
+/// Adaptive large neighborhood search: every iteration removes between 2 and `max_removed`
+/// clusters from the current tour with a destroy operator and reinserts them with a repair
+/// operator, choosing the best vertex of every cluster for its position. The operators are drawn
+/// with probabilities proportional to weights that adapt to how often they led to new best,
+/// improved or accepted solutions. New solutions are accepted as in simulated annealing.
+pub struct Alns<G: Rng> {
+    termination: Termination,
+    rng: G,
+    max_removed: usize,
+}
+
+impl<G: Rng> Alns<G> {
+    pub fn new(termination: Termination, rng: G) -> Self {
+        Self::with_max_removed(termination, rng, DEFAULT_MAX_REMOVED)
+    }
+
+    pub fn with_max_removed(termination: Termination, rng: G, max_removed: usize) -> Self {
+        Self {
+            termination,
+            rng,
+            max_removed,
+        }
+    }
+}
+
+impl<G: Rng, R: Ring + ToF64> ImprovementHeuristic<GtspProblem<R>> for Alns<G> {
+    fn improve(
+        &mut self,
+        instance: &GtspProblem<R>,
+        current: <GtspProblem<R> as Problem>::Solution,
+    ) -> Solution<R> {
+        // At least two clusters are kept, so that every insertion has a predecessor and successor.
+        let max_removed = self.max_removed.min(current.tour().len().saturating_sub(2));
+        if max_removed == 0 {
+            return current;
+        }
+
+        let mut destroy_weights = OperatorWeights::new(DESTROY.len());
+        let mut repair_weights = OperatorWeights::new(REGRET.len());
+        let mut temperature = START_WORSENING * current.weight().to_f64() / LN_2;
+        let mut best = current.clone();
+        let mut current = current;
+        let mut iterations = 0;
+
+        while !self.termination.should_terminate() {
+            let k = self
+                .rng
+                .gen_range(MIN_REMOVED.min(max_removed)..=max_removed);
+            let destroy = destroy_weights.choose(&mut self.rng);
+            let repair = repair_weights.choose(&mut self.rng);
+
+            let mut tour = current.tour().to_owned();
+            let removed = match DESTROY[destroy] {
+                Destroy::Random => random_removal(instance, &mut tour, k, &mut self.rng),
+                Destroy::Worst => worst_removal(instance, &mut tour, k, &mut self.rng),
+                Destroy::Related => related_removal(instance, &mut tour, k, &mut self.rng),
+                Destroy::Segment => segment_removal(instance, &mut tour, k, &mut self.rng),
+            };
+            regret_insertion(instance, &mut tour, removed, REGRET[repair]);
+            let candidate = Solution::new(instance, tour);
+
+            let increase = candidate.weight() - current.weight();
+            let accepted = increase <= 0.into()
+                || self.rng.gen::<f64>() < (-increase.to_f64() / temperature).exp();
+            let score = if candidate.weight() < best.weight() {
+                NEW_BEST_SCORE
+            } else if candidate.weight() < current.weight() {
+                IMPROVED_SCORE
+            } else if accepted {
+                ACCEPTED_SCORE
+            } else {
+                0.0
+            };
+            destroy_weights.reward(destroy, score);
+            repair_weights.reward(repair, score);
+
+            if accepted {
+                if candidate.weight() < best.weight() {
+                    best = candidate.clone();
+                }
+                current = candidate;
+            }
+
+            temperature *= COOLING_RATE;
+            iterations += 1;
+            if iterations % SEGMENT_LENGTH == 0 {
+                destroy_weights.end_segment();
+                repair_weights.end_segment();
+            }
+            self.termination.iteration();
+        }
+
+        best
+    }
+}
+
+/// A random rank below `len`, preferring the first ranks more strongly for larger `randomness`.
+fn randomized_rank(rng: &mut impl Rng, len: usize, randomness: i32) -> usize {
+    ((rng.gen::<f64>().powi(randomness) * len as f64) as usize).min(len - 1)
+}
+
+fn random_removal<R: Ring>(
+    instance: &GtspProblem<R>,
+    tour: &mut Vec<usize>,
+    k: usize,
+    rng: &mut impl Rng,
+) -> Vec<usize> {
+    let mut is_removed = vec![false; tour.len()];
+    for p in sample(rng, tour.len(), k) {
+        is_removed[p] = true;
+    }
+    let (removed, kept) = tour
+        .iter()
+        .zip(is_removed)
+        .partition::<Vec<_>, _>(|(_, is_removed)| *is_removed);
+    let removed = removed
+        .into_iter()
+        .map(|(&v, _)| instance.cluster_of(v))
+        .collect();
+    *tour = kept.into_iter().map(|(&v, _)| v).collect();
+    removed
+}
+
+fn worst_removal<R: Ring>(
+    instance: &GtspProblem<R>,
+    tour: &mut Vec<usize>,
+    k: usize,
+    rng: &mut impl Rng,
+) -> Vec<usize> {
+    (0..k)
+        .map(|_| {
+            let m = tour.len();
+            let saving = |p: usize| {
+                let (a, v, b) = (tour[(p + m - 1) % m], tour[p], tour[(p + 1) % m]);
+                instance.dist(a, v) + instance.dist(v, b) - instance.dist(a, b)
+            };
+            let ranked = (0..m)
+                .sorted_by_cached_key(|&p| Reverse(saving(p)))
+                .collect_vec();
+            let p = ranked[randomized_rank(rng, m, WORST_RANDOMNESS)];
+            instance.cluster_of(tour.remove(p))
+        })
+        .collect()
+}
+
+fn related_removal<R: Ring>(
+    instance: &GtspProblem<R>,
+    tour: &mut Vec<usize>,
+    k: usize,
+    rng: &mut impl Rng,
+) -> Vec<usize> {
+    let mut removed = vec![tour.remove(rng.gen_range(0..tour.len()))];
+    while removed.len() < k {
+        let &r = removed.choose(rng).unwrap();
+        let ranked = (0..tour.len())
+            .sorted_by_cached_key(|&p| instance.dist(r, tour[p]).min(instance.dist(tour[p], r)))
+            .collect_vec();
+        let p = ranked[randomized_rank(rng, ranked.len(), RELATED_RANDOMNESS)];
+        removed.push(tour.remove(p));
+    }
+    removed
+        .into_iter()
+        .map(|v| instance.cluster_of(v))
+        .collect()
+}
+
+fn segment_removal<R: Ring>(
+    instance: &GtspProblem<R>,
+    tour: &mut Vec<usize>,
+    k: usize,
+    rng: &mut impl Rng,
+) -> Vec<usize> {
+    let start = rng.gen_range(0..tour.len());
+    tour.rotate_left(start);
+    tour.drain(..k).map(|v| instance.cluster_of(v)).collect()
+}
+
+/// Inserts the clusters one at a time, each time choosing the cluster with the largest regret,
+/// the sum of the differences between its best and its `k - 1` next best insertion costs, at its
+/// best position. Ties are broken by the best insertion cost.
+fn regret_insertion<R: Ring>(
+    instance: &GtspProblem<R>,
+    tour: &mut Vec<usize>,
+    mut removed: Vec<usize>,
+    k: usize,
+) {
+    while !removed.is_empty() {
+        let (i, (_, p, v)) = removed
+            .iter()
+            .map(|&c| insertions(instance, tour, c))
+            .enumerate()
+            .max_by_key(|(_, costs)| {
+                let best = costs[0].0;
+                let regret = costs[..k.min(costs.len())]
+                    .iter()
+                    .map(|&(cost, ..)| cost - best)
+                    .sum::<R>();
+                (regret, Reverse(best))
+            })
+            .map(|(i, costs)| (i, costs[0]))
+            .unwrap();
+        tour.insert(p + 1, v);
+        removed.swap_remove(i);
+    }
+}
+
+/// For every position `p` of the tour, the cost of inserting the best vertex `v` of the cluster
+/// between the vertices at `p` and `p + 1`, as `(cost, p, v)` sorted by cost.
+fn insertions<R: Ring>(
+    instance: &GtspProblem<R>,
+    tour: &[usize],
+    cluster: usize,
+) -> Vec<(R, usize, usize)> {
+    let m = tour.len();
+    (0..m)
+        .map(|p| {
+            let (a, b) = (tour[p], tour[(p + 1) % m]);
+            instance.clusters()[cluster]
+                .iter()
+                .map(|&v| {
+                    let cost = instance.dist(a, v) + instance.dist(v, b) - instance.dist(a, b);
+                    (cost, p, v)
+                })
+                .min()
+                .unwrap()
+        })
+        .sorted_unstable()
+        .collect()
+}
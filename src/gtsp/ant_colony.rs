@@ -0,0 +1,172 @@
+use rand::{
+    distributions::{Distribution as _, WeightedIndex},
+    Rng,
+};
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    termination::Termination,
+    ImprovementHeuristic, MetaHeuristic, Problem, Ring, ToF64,
+};
+
+const ANTS: usize = 25;
+
+/// The relative influence of the distance on the choice of the next vertex, compared to the
+/// pheromone.
+const BETA: i32 = 2;
+const EVAPORATION: f64 = 0.02;
+/// The probability that an ant constructs the best tour once the pheromone has converged, which
+/// determines the lower pheromone bound (Stützle and Hoos, 2000).
+const P_BEST: f64 = 0.05;
+/// Every this many iterations, pheromone is deposited on the best tour so far instead of the
+/// best tour of the iteration.
+const BEST_SO_FAR_FREQUENCY: usize = 5;
+
+/// Leaves the tours of the ants as they are constructed.
+pub struct NoLocalSearch;
+
+impl<P: Problem> ImprovementHeuristic<P> for NoLocalSearch {
+    fn improve(&mut self, _instance: &P, current: P::Solution) -> P::Solution {
+        current
+    }
+}
+
+/// MAX-MIN ant system with pheromone on the arcs between vertices. Every ant starts at a random
+/// vertex and repeatedly moves to a vertex of an unvisited cluster, chosen among the nearest
+/// vertices of the current one (see `GtspProblem::neighbors`) with probability proportional to
+/// the pheromone times the squared inverse distance; if all of them are visited, the best vertex
+/// of any unvisited cluster is taken. The tour of every ant is improved by the local search
+/// before pheromone is deposited on the best tour, and the pheromone is kept within the MMAS
+/// bounds.
+pub struct AntColony<G, L> {
+    termination: Termination,
+    rng: G,
+    local_search: L,
+}
+
+impl<G: Rng> AntColony<G, NoLocalSearch> {
+    pub fn new(termination: Termination, rng: G) -> Self {
+        Self::with_local_search(termination, rng, NoLocalSearch)
+    }
+}
+
+impl<G: Rng, L> AntColony<G, L> {
+    pub fn with_local_search(termination: Termination, rng: G, local_search: L) -> Self {
+        Self {
+            termination,
+            rng,
+            local_search,
+        }
+    }
+}
+
+impl<G, L, R> MetaHeuristic<GtspProblem<R>> for AntColony<G, L>
+where
+    G: Rng,
+    L: ImprovementHeuristic<GtspProblem<R>>,
+    R: Ring + ToF64,
+{
+    fn run(mut self, instance: &GtspProblem<R>) -> Solution<R> {
+        let n = instance.number_of_vertices();
+        let attractiveness = |u: usize, v: usize| 1.0 / (instance.dist(u, v).to_f64() + 1.0);
+
+        let mut pheromone = vec![vec![1.0; n]; n];
+        let mut best: Option<Solution<R>> = None;
+        let mut iterations = 0;
+
+        loop {
+            let iteration_best = (0..ANTS)
+                .map(|_| {
+                    let tour = construct_tour(instance, &pheromone, &mut self.rng, attractiveness);
+                    self.local_search
+                        .improve(instance, Solution::new(instance, tour))
+                })
+                .min_by_key(|s| s.weight())
+                .expect("no ants");
+
+            let is_first = best.is_none();
+            if best
+                .as_ref()
+                .map_or(true, |b| iteration_best.weight() < b.weight())
+            {
+                best = Some(iteration_best.clone());
+            }
+            let best = best.as_ref().unwrap();
+
+            self.termination.iteration();
+            iterations += 1;
+            if self.termination.should_terminate() {
+                break;
+            }
+
+            let tau_max = 1.0 / (EVAPORATION * best.weight().to_f64().max(1.0));
+            let root = P_BEST.powf(1.0 / instance.number_of_clusters() as f64);
+            let average_choices = (instance.number_of_clusters() as f64 / 2.0).max(2.0);
+            let tau_min = tau_max * (1.0 - root) / ((average_choices - 1.0) * root);
+
+            if is_first {
+                pheromone = vec![vec![tau_max; n]; n];
+            }
+            for row in &mut pheromone {
+                for tau in row {
+                    *tau *= 1.0 - EVAPORATION;
+                }
+            }
+            let deposited = if iterations % BEST_SO_FAR_FREQUENCY == 0 {
+                best
+            } else {
+                &iteration_best
+            };
+            let amount = 1.0 / deposited.weight().to_f64().max(1.0);
+            let tour = deposited.tour();
+            for k in 0..tour.len() {
+                let (u, v) = (tour[k], tour[(k + 1) % tour.len()]);
+                pheromone[u][v] += amount;
+                if instance.is_symmetric() {
+                    pheromone[v][u] += amount;
+                }
+            }
+            for row in &mut pheromone {
+                for tau in row {
+                    *tau = tau.clamp(tau_min, tau_max);
+                }
+            }
+        }
+
+        best.unwrap()
+    }
+}
+
+fn construct_tour<R: Ring>(
+    instance: &GtspProblem<R>,
+    pheromone: &[Vec<f64>],
+    rng: &mut impl Rng,
+    attractiveness: impl Fn(usize, usize) -> f64,
+) -> Vec<usize> {
+    let weight = |u: usize, v: usize| pheromone[u][v] * attractiveness(u, v).powi(BETA);
+
+    let mut visited = vec![false; instance.number_of_clusters()];
+    let mut tour = vec![rng.gen_range(0..instance.number_of_vertices())];
+    visited[instance.cluster_of(tour[0])] = true;
+
+    while tour.len() < instance.number_of_clusters() {
+        let u = *tour.last().unwrap();
+        let candidates = instance
+            .neighbors(u)
+            .iter()
+            .copied()
+            .filter(|&v| !visited[instance.cluster_of(v)])
+            .collect::<Vec<_>>();
+        let next = match WeightedIndex::new(candidates.iter().map(|&v| weight(u, v))) {
+            Ok(distribution) => candidates[distribution.sample(rng)],
+            Err(_) => (0..instance.number_of_vertices())
+                .filter(|&v| !visited[instance.cluster_of(v)])
+                .max_by(|&v, &w| weight(u, v).total_cmp(&weight(u, w)))
+                .unwrap(),
+        };
+        visited[instance.cluster_of(next)] = true;
+        tour.push(next);
+    }
+
+    tour
+}
@@ -0,0 +1,258 @@
+use std::iter;
+
+use itertools::Itertools as _;
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    termination::Termination,
+    MetaHeuristic, Ring,
+};
+
+/// Exact depth-first branch-and-bound solver. Partial tours start in the smallest cluster and are
+/// extended by one vertex of an unvisited cluster at a time. A partial tour is pruned if one of
+/// two lower bounds on its completion is not better than the best known tour:
+///
+/// - the sum of the cheapest arcs entering each unvisited cluster and the start vertex, and
+/// - a minimum spanning tree on the unvisited clusters, the last vertex and the start vertex, with
+///   the cheapest arc in either direction between them as edge weights.
+///
+/// The search is seeded with the solution of another heuristic as the upper bound.
+pub struct BranchAndBound<U> {
+    termination: Termination,
+    upper_bound: U,
+}
+
+impl<U> BranchAndBound<U> {
+    pub fn new(termination: Termination, upper_bound: U) -> Self {
+        Self {
+            termination,
+            upper_bound,
+        }
+    }
+}
+
+/// A solution together with a proven lower bound on the optimum.
+#[derive(Debug, Clone)]
+pub struct BoundedSolution<R> {
+    solution: Solution<R>,
+    lower_bound: R,
+}
+
+impl<R: Ring> BoundedSolution<R> {
+    pub fn solution(&self) -> &Solution<R> {
+        &self.solution
+    }
+    pub fn into_solution(self) -> Solution<R> {
+        self.solution
+    }
+    pub fn lower_bound(&self) -> R {
+        self.lower_bound
+    }
+    pub fn gap(&self) -> R {
+        self.solution.weight() - self.lower_bound
+    }
+    pub fn is_optimal(&self) -> bool {
+        self.gap() == 0.into()
+    }
+}
+
+struct Node<R> {
+    depth: usize,
+    vertex: usize,
+    cost: R,
+    bound: R,
+}
+
+struct Bounds<R> {
+    /// For every cluster, the cheapest arc entering it.
+    min_in: Vec<R>,
+    /// For every pair of clusters, the cheapest arc between them in either direction.
+    cluster_dist: Vec<Vec<R>>,
+    /// For every vertex and cluster, the cheapest arc between them in either direction.
+    vertex_cluster_dist: Vec<Vec<R>>,
+}
+
+impl<R: Ring> Bounds<R> {
+    fn new(instance: &GtspProblem<R>) -> Self {
+        let m = instance.clusters.len();
+        let arcs = || {
+            (0..instance.number_of_vertices)
+                .tuple_combinations()
+                .filter(|&(u, v)| instance.cluster_of(u) != instance.cluster_of(v))
+        };
+
+        let mut min_in = vec![None::<R>; m];
+        let mut cluster_dist = vec![vec![None::<R>; m]; m];
+        let mut vertex_cluster_dist = vec![vec![None::<R>; m]; instance.number_of_vertices];
+        let relax = |d: &mut Option<R>, w: R| {
+            if d.filter(|&d| d <= w).is_none() {
+                *d = Some(w);
+            }
+        };
+        for (u, v) in arcs() {
+            let (cu, cv) = (instance.cluster_of(u), instance.cluster_of(v));
+            relax(&mut min_in[cv], instance.dist(u, v));
+            relax(&mut min_in[cu], instance.dist(v, u));
+            let w = instance.dist(u, v).min(instance.dist(v, u));
+            relax(&mut cluster_dist[cu][cv], w);
+            relax(&mut cluster_dist[cv][cu], w);
+            relax(&mut vertex_cluster_dist[u][cv], w);
+            relax(&mut vertex_cluster_dist[v][cu], w);
+        }
+
+        let unwrap_all =
+            |ds: Vec<Option<R>>| ds.into_iter().map(|d| d.unwrap_or(0.into())).collect_vec();
+        Self {
+            min_in: unwrap_all(min_in),
+            cluster_dist: cluster_dist.into_iter().map(unwrap_all).collect(),
+            vertex_cluster_dist: vertex_cluster_dist.into_iter().map(unwrap_all).collect(),
+        }
+    }
+
+    /// Weight of a minimum spanning tree on the given clusters, the last vertex of the partial
+    /// tour and the start vertex, computed with Prim's algorithm.
+    fn spanning_tree(
+        &self,
+        instance: &GtspProblem<R>,
+        last: usize,
+        start: usize,
+        unvisited: &[usize],
+    ) -> R {
+        // The start vertex is the root, so the other nodes are the last vertex and the clusters.
+        let mut dist_to_tree = iter::once(if last == start {
+            0.into()
+        } else {
+            instance.dist(last, start).min(instance.dist(start, last))
+        })
+        .chain(
+            unvisited
+                .iter()
+                .map(|&c| self.vertex_cluster_dist[start][c]),
+        )
+        .map(Some)
+        .collect_vec();
+
+        let mut weight = R::from(0);
+        while let Some((i, d)) = dist_to_tree
+            .iter()
+            .enumerate()
+            .filter_map(|(i, d)| Some((i, (*d)?)))
+            .min_by_key(|&(_, d)| d)
+        {
+            weight = weight + d;
+            dist_to_tree[i] = None;
+            for (j, dj) in dist_to_tree.iter_mut().enumerate() {
+                let Some(dj) = dj else { continue };
+                let w = match (i, j) {
+                    (0, 0) => unreachable!(),
+                    (0, j) | (j, 0) => self.vertex_cluster_dist[last][unvisited[j - 1]],
+                    (i, j) => self.cluster_dist[unvisited[i - 1]][unvisited[j - 1]],
+                };
+                *dj = (*dj).min(w);
+            }
+        }
+        weight
+    }
+}
+
+impl<U> BranchAndBound<U> {
+    pub fn solve<R: Ring>(mut self, instance: &GtspProblem<R>) -> BoundedSolution<R>
+    where
+        U: MetaHeuristic<GtspProblem<R>>,
+    {
+        let m = instance.clusters.len();
+        let bounds = Bounds::new(instance);
+        let mut best = self.upper_bound.run(instance);
+
+        let start_cluster = (0..m).min_by_key(|&c| instance.clusters[c].len()).unwrap();
+        let in_sum_all = bounds.min_in.iter().copied().sum::<R>();
+        let other_clusters = (0..m).filter(|&c| c != start_cluster).collect_vec();
+        let mut stack = instance.clusters[start_cluster]
+            .iter()
+            .map(|&s| Node {
+                depth: 0,
+                vertex: s,
+                cost: 0.into(),
+                bound: in_sum_all.max(bounds.spanning_tree(instance, s, s, &other_clusters)),
+            })
+            .collect_vec();
+
+        let mut path = Vec::with_capacity(m);
+        let mut visited = vec![false; m];
+        // Sum of `bounds.min_in` over all unvisited clusters.
+        let mut in_sum = in_sum_all;
+
+        while !self.termination.should_terminate() {
+            let Some(node) = stack.pop() else {
+                break;
+            };
+            self.termination.iteration();
+
+            for v in path.drain(node.depth..) {
+                visited[instance.cluster_of(v)] = false;
+                in_sum = in_sum + bounds.min_in[instance.cluster_of(v)];
+            }
+            path.push(node.vertex);
+            visited[instance.cluster_of(node.vertex)] = true;
+            in_sum = in_sum - bounds.min_in[instance.cluster_of(node.vertex)];
+
+            if node.bound >= best.weight() {
+                continue;
+            }
+
+            let start = path[0];
+            if path.len() == m {
+                let weight = node.cost + instance.dist(node.vertex, start);
+                if weight < best.weight() {
+                    best = Solution::new(instance, path.clone()).assert_weight(weight);
+                }
+                continue;
+            }
+
+            let unvisited = (0..m).filter(|&c| !visited[c]).collect_vec();
+            let tree_bound =
+                node.cost + bounds.spanning_tree(instance, node.vertex, start, &unvisited);
+            if tree_bound >= best.weight() {
+                continue;
+            }
+
+            let start_in = bounds.min_in[start_cluster];
+            let children = unvisited
+                .iter()
+                .flat_map(|&c| instance.clusters[c].iter().copied())
+                .map(|w| {
+                    let cost = node.cost + instance.dist(node.vertex, w);
+                    Node {
+                        depth: node.depth + 1,
+                        vertex: w,
+                        cost,
+                        bound: tree_bound
+                            .max(cost + in_sum - bounds.min_in[instance.cluster_of(w)] + start_in),
+                    }
+                })
+                .filter(|child| child.bound < best.weight())
+                .sorted_by_key(|child| child.cost)
+                .rev();
+            stack.extend(children);
+        }
+
+        let lower_bound = stack
+            .iter()
+            .map(|node| node.bound)
+            .chain([best.weight()])
+            .min()
+            .unwrap();
+        BoundedSolution {
+            solution: best,
+            lower_bound,
+        }
+    }
+}
+
+impl<R: Ring, U: MetaHeuristic<GtspProblem<R>>> MetaHeuristic<GtspProblem<R>>
+    for BranchAndBound<U>
+{
+    fn run(self, instance: &GtspProblem<R>) -> Solution<R> {
+        self.solve(instance).into_solution()
+    }
+}
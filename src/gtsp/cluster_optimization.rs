@@ -1,10 +1,21 @@
+use std::marker::PhantomData;
+
 use itertools::Itertools as _;
+use rand::Rng;
 
 use crate::{
-    gtsp::{GtspProblem, Solution},
-    ImprovementHeuristic, Ring,
+    gtsp::{neighborhoods::Edge, GtspProblem, Solution},
+    ImprovementHeuristic, Move, MoveNeighborhood, Problem, Ring, TabuNeighborhood,
 };
 
+/// Selects the best vertex of every cluster for the cluster order of the tour, using shortest
+/// paths through the layers of clusters starting and ending in the smallest cluster.
+///
+/// A single pass from all vertices of the smallest cluster at once gives a lower bound on the
+/// best tour through each of them. Exact passes are only run from the vertices whose bound is
+/// below the best tour found so far, starting with the input tour, in the order of their bounds.
+/// In the worst case, there is one exact pass per vertex of the smallest cluster, but usually the
+/// bounds leave only a few.
 pub struct ClusterOptimization;
 
 impl<R: Ring> ImprovementHeuristic<GtspProblem<R>> for ClusterOptimization {
@@ -13,58 +24,331 @@ impl<R: Ring> ImprovementHeuristic<GtspProblem<R>> for ClusterOptimization {
         instance: &GtspProblem<R>,
         current: <GtspProblem<R> as crate::Problem>::Solution,
     ) -> Solution<R> {
-        let mut cluster_order = vec![0; instance.clusters.len()];
-        for (i, c) in instance.clusters.iter().enumerate() {
-            cluster_order[current.tour().iter().position(|v| c.contains(v)).unwrap()] = i;
+        let m = current.tour().len();
+        let starting_position = (0..m)
+            .min_by_key(|&k| instance.clusters[instance.cluster_of(current.tour()[k])].len())
+            .unwrap();
+        let layers = (0..=m)
+            .map(|l| {
+                let v = current.tour()[(starting_position + l) % m];
+                &instance.clusters[instance.cluster_of(v)][..]
+            })
+            .collect_vec();
+        let starts = layers[0].len();
+
+        let (bounds, _) = layered_shortest_paths(instance, &layers, vec![Some(0.into()); starts]);
+        let mut best_weight = current.weight();
+        let mut best_tour = None;
+        for s in (0..starts).sorted_by_key(|&s| bounds[s]) {
+            if bounds[s].unwrap() >= best_weight {
+                break;
+            }
+            let mut initial = vec![None; starts];
+            initial[s] = Some(0.into());
+            let (dist, parents) = layered_shortest_paths(instance, &layers, initial);
+            let weight = dist[s].unwrap();
+            if weight < best_weight {
+                best_weight = weight;
+                let mut v_i = s;
+                let mut tour = (0..m)
+                    .rev()
+                    .map(|l| {
+                        v_i = parents[l][v_i].unwrap();
+                        layers[l][v_i]
+                    })
+                    .collect_vec();
+                tour.reverse();
+                best_tour = Some(tour);
+            }
         }
 
-        let starting_cluster = cluster_order
-            .iter()
-            .enumerate()
-            .min_by_key(|(_, &i)| instance.clusters[i].len())
-            .unwrap()
-            .0;
-
-        (0..instance.clusters[cluster_order[starting_cluster]].len())
-            .map(|s_index| {
-                let mut parent = vec![None; instance.number_of_vertices];
-                let mut current_dist =
-                    vec![None; instance.clusters[cluster_order[starting_cluster]].len()];
-                current_dist[s_index] = Some(R::from(0));
-
-                for i in 0..instance.clusters.len() {
-                    let current_cluster_i = (starting_cluster + i) % instance.clusters.len();
-                    let next_cluster_i = (current_cluster_i + 1) % instance.clusters.len();
-                    let current_cluster = cluster_order[current_cluster_i];
-                    let next_cluster = cluster_order[next_cluster_i];
-
-                    let mut next_dist = vec![None; instance.clusters[next_cluster].len()];
-
-                    let edges = instance.clusters[current_cluster]
-                        .iter()
-                        .enumerate()
-                        .cartesian_product(instance.clusters[next_cluster].iter().enumerate());
-                    for ((a_i, &a), (b_i, &b)) in edges {
-                        if let Some(a_dist) = current_dist[a_i] {
-                            let relaxed_dist = a_dist + instance.dist(a, b);
-                            if next_dist[b_i].filter(|&d| d <= relaxed_dist).is_none() {
-                                next_dist[b_i] = Some(relaxed_dist);
-                                parent[b] = Some(a);
-                            }
-                        }
-                    }
-
-                    current_dist = next_dist;
-                }
+        // Ties keep the input tour.
+        match best_tour {
+            Some(tour) => Solution::new(instance, tour),
+            None => current,
+        }
+    }
+}
 
-                let mut tour = vec![instance.clusters[cluster_order[starting_cluster]][s_index]];
-                while tour.len() < instance.clusters.len() {
-                    tour.push(parent[*tour.last().unwrap()].unwrap());
+/// The shortest distances from the first layer, with the given distances of its vertices, to
+/// every vertex of the last layer, and, for every layer after the first, the index of the
+/// predecessor of every vertex in the previous layer. The first and the last layer can be the same
+/// cluster, so predecessors cannot be stored per vertex.
+fn layered_shortest_paths<R: Ring>(
+    instance: &GtspProblem<R>,
+    layers: &[&[usize]],
+    initial: Vec<Option<R>>,
+) -> (Vec<Option<R>>, Vec<Vec<Option<usize>>>) {
+    let mut dist = initial;
+    let mut parents = Vec::with_capacity(layers.len() - 1);
+    for (current_layer, next_layer) in layers.iter().tuple_windows() {
+        let mut next_dist = vec![None; next_layer.len()];
+        let mut next_parent = vec![None; next_layer.len()];
+        for (a_i, &a) in current_layer.iter().enumerate() {
+            let Some(a_dist) = dist[a_i] else {
+                continue;
+            };
+            for (b_i, &b) in next_layer.iter().enumerate() {
+                let relaxed_dist = a_dist + instance.dist(a, b);
+                if next_dist[b_i].filter(|&d| d <= relaxed_dist).is_none() {
+                    next_dist[b_i] = Some(relaxed_dist);
+                    next_parent[b_i] = Some(a_i);
                 }
-                tour.reverse();
-                Solution::new(instance, tour)
+            }
+        }
+        dist = next_dist;
+        parents.push(next_parent);
+    }
+    (dist, parents)
+}
+
+/// Re-selects the vertices at the `window` positions starting at `start` optimally for the fixed
+/// vertices before and after them. Returns whether the tour was improved.
+pub fn optimize_window<R: Ring>(
+    instance: &GtspProblem<R>,
+    tour: &mut [usize],
+    start: usize,
+    window: usize,
+) -> bool {
+    let n = tour.len();
+    let window = window.min(n.saturating_sub(2));
+    if window == 0 {
+        return false;
+    }
+    let position = |k: usize| (start + k) % n;
+    let (before, after) = (tour[(start + n - 1) % n], tour[position(window)]);
+    let layers = (0..window)
+        .map(|k| &instance.clusters[instance.cluster_of(tour[position(k)])])
+        .collect_vec();
+    let layer = |k: usize| layers[k];
+
+    let mut dist = layer(0)
+        .iter()
+        .map(|&v| instance.dist(before, v))
+        .collect_vec();
+    // The index of the predecessor in the previous layer, for every layer after the first.
+    let mut parents = Vec::with_capacity(window - 1);
+    for k in 1..window {
+        let (previous_layer, current_layer) = (layer(k - 1), layer(k));
+        let (next_dist, parent): (Vec<_>, Vec<_>) = current_layer
+            .iter()
+            .map(|&b| {
+                previous_layer
+                    .iter()
+                    .enumerate()
+                    .map(|(a_i, &a)| (dist[a_i] + instance.dist(a, b), a_i))
+                    .min()
+                    .unwrap()
             })
-            .min_by_key(|tour| tour.weight())
-            .unwrap()
+            .unzip();
+        dist = next_dist;
+        parents.push(parent);
+    }
+    let (new_cost, mut v_i) = layer(window - 1)
+        .iter()
+        .enumerate()
+        .map(|(v_i, &v)| (dist[v_i] + instance.dist(v, after), v_i))
+        .min()
+        .unwrap();
+
+    let old_cost = (0..=window)
+        .map(|k| {
+            let u = if k == 0 {
+                before
+            } else {
+                tour[position(k - 1)]
+            };
+            instance.dist(u, tour[position(k)])
+        })
+        .sum::<R>();
+    if new_cost >= old_cost {
+        return false;
+    }
+
+    for k in (0..window).rev() {
+        let vertex = layer(k)[v_i];
+        if k > 0 {
+            v_i = parents[k - 1][v_i];
+        }
+        tour[position(k)] = vertex;
+    }
+    true
+}
+
+/// Applies `optimize_window` to the windows of `window` consecutive clusters starting at every
+/// position of the tour.
+pub struct WindowClusterOptimization {
+    window: usize,
+}
+
+impl WindowClusterOptimization {
+    pub fn new(window: usize) -> Self {
+        Self { window }
+    }
+}
+
+impl<R: Ring> ImprovementHeuristic<GtspProblem<R>> for WindowClusterOptimization {
+    fn improve(
+        &mut self,
+        instance: &GtspProblem<R>,
+        current: <GtspProblem<R> as crate::Problem>::Solution,
+    ) -> Solution<R> {
+        let mut tour = current.tour().to_owned();
+        let mut improved = false;
+        for start in 0..tour.len() {
+            improved |= optimize_window(instance, &mut tour, start, self.window);
+        }
+        if improved {
+            Solution::new(instance, tour)
+        } else {
+            current
+        }
+    }
+}
+
+/// The moves of `N`, each followed by `optimize_window` on the windows of `WINDOW` clusters
+/// around the arcs the move added, so that Cluster Optimization is applied after every move.
+/// The score increase of the moves does not include the improvement by Cluster Optimization.
+pub struct WithWindowCo<N, const WINDOW: usize = 3>(PhantomData<N>);
+
+pub struct WindowCoMove<'p, R: Ring, N: MoveNeighborhood<GtspProblem<R>>>
+where
+    R: 'p,
+{
+    problem: &'p GtspProblem<R>,
+    inner: N::Move<'p>,
+    window: usize,
+}
+
+impl<'p, R, N> Move<GtspProblem<R>> for WindowCoMove<'p, R, N>
+where
+    R: Ring,
+    N: TabuNeighborhood<GtspProblem<R>, Attribute = Edge>,
+{
+    fn score_increase(&self) -> <GtspProblem<R> as Problem>::Score {
+        self.inner.score_increase()
+    }
+
+    fn is_improving(&self) -> bool {
+        self.inner.is_improving()
+    }
+
+    fn into_solution(self) -> <GtspProblem<R> as Problem>::Solution {
+        let changed = N::added_attributes(&self.inner)
+            .into_iter()
+            .flat_map(|(u, v)| [u, v])
+            .collect_vec();
+        let solution = self.inner.into_solution();
+        let mut tour = solution.tour().to_owned();
+        let n = tour.len();
+        // Cluster Optimization does not move clusters, so the positions stay valid.
+        let starts = changed
+            .into_iter()
+            .filter_map(|u| tour.iter().position(|&v| v == u))
+            .map(|k| (k + n - self.window.min(n) / 2) % n)
+            .unique()
+            .collect_vec();
+        let mut improved = false;
+        for start in starts {
+            improved |= optimize_window(self.problem, &mut tour, start, self.window);
+        }
+        if improved {
+            Solution::new(self.problem, tour)
+        } else {
+            solution
+        }
+    }
+}
+
+impl<R, N, const WINDOW: usize> MoveNeighborhood<GtspProblem<R>> for WithWindowCo<N, WINDOW>
+where
+    R: Ring,
+    N: TabuNeighborhood<GtspProblem<R>, Attribute = Edge> + 'static,
+{
+    type Move<'c> = WindowCoMove<'c, R, N> where R: 'c;
+
+    type Iter<'c> = WindowCoMoves<'c, R, N> where R: 'c;
+
+    fn moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+    ) -> Self::Iter<'c> {
+        WindowCoMoves {
+            problem,
+            inner: N::moves_iter(problem, current),
+            window: WINDOW,
+        }
+    }
+
+    fn random_moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Self::Iter<'c> {
+        WindowCoMoves {
+            problem,
+            inner: N::random_moves_iter(problem, current, rng),
+            window: WINDOW,
+        }
+    }
+
+    fn random_move<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Option<Self::Move<'c>> {
+        Some(WindowCoMove {
+            problem,
+            inner: N::random_move(problem, current, rng)?,
+            window: WINDOW,
+        })
+    }
+}
+
+impl<R, N, const WINDOW: usize> TabuNeighborhood<GtspProblem<R>> for WithWindowCo<N, WINDOW>
+where
+    R: Ring,
+    N: TabuNeighborhood<GtspProblem<R>, Attribute = Edge> + 'static,
+{
+    type Attribute = Edge;
+    type Attributes = N::Attributes;
+
+    fn removed_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        N::removed_attributes(&m.inner)
+    }
+
+    fn added_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        N::added_attributes(&m.inner)
+    }
+}
+
+pub struct WindowCoMoves<'p, R: Ring, N: MoveNeighborhood<GtspProblem<R>>>
+where
+    R: 'p,
+{
+    problem: &'p GtspProblem<R>,
+    inner: N::Iter<'p>,
+    window: usize,
+}
+
+impl<'p, R, N> Iterator for WindowCoMoves<'p, R, N>
+where
+    R: Ring,
+    N: TabuNeighborhood<GtspProblem<R>, Attribute = Edge>,
+{
+    type Item = WindowCoMove<'p, R, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(WindowCoMove {
+            problem: self.problem,
+            inner: self.inner.next()?,
+            window: self.window,
+        })
     }
 }
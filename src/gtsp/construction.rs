@@ -0,0 +1,161 @@
+use itertools::Itertools as _;
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    InitialSolution, Ring,
+};
+
+/// Starts at every vertex of the smallest cluster and repeatedly moves to the closest vertex of
+/// any unvisited cluster. The best of the resulting tours is returned.
+pub struct NearestNeighbor;
+
+impl<R: Ring> InitialSolution<GtspProblem<R>> for NearestNeighbor {
+    fn make_intial_solution(&mut self, instance: &GtspProblem<R>) -> Solution<R> {
+        let smallest_cluster = instance
+            .clusters
+            .iter()
+            .min_by_key(|c| c.len())
+            .expect("no clusters");
+
+        smallest_cluster
+            .iter()
+            .map(|&start| {
+                let mut visited = vec![false; instance.clusters.len()];
+                visited[instance.cluster_of(start)] = true;
+                let mut tour = vec![start];
+
+                while tour.len() < instance.clusters.len() {
+                    let last = *tour.last().unwrap();
+                    let next = (0..instance.number_of_vertices)
+                        .filter(|&v| !visited[instance.cluster_of(v)])
+                        .min_by_key(|&v| instance.dist(last, v))
+                        .unwrap();
+                    visited[instance.cluster_of(next)] = true;
+                    tour.push(next);
+                }
+
+                Solution::new(instance, tour)
+            })
+            .min_by_key(|s| s.weight())
+            .unwrap()
+    }
+}
+
+/// Starts with the cheapest two-cluster cycle and repeatedly inserts the vertex of an unvisited
+/// cluster that increases the tour weight the least.
+pub struct CheapestInsertion;
+
+impl<R: Ring> InitialSolution<GtspProblem<R>> for CheapestInsertion {
+    fn make_intial_solution(&mut self, instance: &GtspProblem<R>) -> Solution<R> {
+        let (u, v) = (0..instance.number_of_vertices)
+            .tuple_combinations()
+            .filter(|&(u, v)| instance.cluster_of(u) != instance.cluster_of(v))
+            .min_by_key(|&(u, v)| instance.dist(u, v) + instance.dist(v, u))
+            .expect("less than two clusters");
+
+        let mut visited = vec![false; instance.clusters.len()];
+        visited[instance.cluster_of(u)] = true;
+        visited[instance.cluster_of(v)] = true;
+        let mut tour = vec![u, v];
+
+        while tour.len() < instance.clusters.len() {
+            let (_, position, vertex) = cheapest_insertion(
+                instance,
+                &tour,
+                (0..instance.number_of_vertices).filter(|&v| !visited[instance.cluster_of(v)]),
+            );
+            visited[instance.cluster_of(vertex)] = true;
+            tour.insert(position, vertex);
+        }
+
+        Solution::new(instance, tour)
+    }
+}
+
+/// Starts with the two clusters that are farthest apart and repeatedly inserts the unvisited
+/// cluster farthest from the tour, choosing its vertex and position by cheapest insertion.
+pub struct FarthestInsertion;
+
+impl<R: Ring> InitialSolution<GtspProblem<R>> for FarthestInsertion {
+    fn make_intial_solution(&mut self, instance: &GtspProblem<R>) -> Solution<R> {
+        let cycle_cost = |(u, v): (usize, usize)| instance.dist(u, v) + instance.dist(v, u);
+        let (u, v) = instance
+            .clusters
+            .iter()
+            .tuple_combinations()
+            .map(|(a, b)| {
+                a.iter()
+                    .copied()
+                    .cartesian_product(b.iter().copied())
+                    .min_by_key(|&e| cycle_cost(e))
+                    .unwrap()
+            })
+            .max_by_key(|&e| cycle_cost(e))
+            .expect("less than two clusters");
+
+        let mut visited = vec![false; instance.clusters.len()];
+        visited[instance.cluster_of(u)] = true;
+        visited[instance.cluster_of(v)] = true;
+        let mut tour = vec![u, v];
+
+        // For every vertex, the distance from the closest vertex in the tour.
+        let mut dist_to_tour = (0..instance.number_of_vertices)
+            .map(|w| {
+                [u, v]
+                    .into_iter()
+                    .filter(|&t| t != w)
+                    .map(|t| instance.dist(t, w))
+                    .min()
+                    .unwrap()
+            })
+            .collect_vec();
+
+        while tour.len() < instance.clusters.len() {
+            let farthest_cluster = (0..instance.clusters.len())
+                .filter(|&c| !visited[c])
+                .max_by_key(|&c| {
+                    instance.clusters[c]
+                        .iter()
+                        .map(|&w| dist_to_tour[w])
+                        .min()
+                        .unwrap()
+                })
+                .unwrap();
+
+            let (_, position, vertex) = cheapest_insertion(
+                instance,
+                &tour,
+                instance.clusters[farthest_cluster].iter().copied(),
+            );
+            visited[farthest_cluster] = true;
+            tour.insert(position, vertex);
+
+            for (w, d) in dist_to_tour.iter_mut().enumerate() {
+                if w != vertex {
+                    *d = (*d).min(instance.dist(vertex, w));
+                }
+            }
+        }
+
+        Solution::new(instance, tour)
+    }
+}
+
+/// Finds the candidate vertex and the position in the tour (as an index for `Vec::insert`) that
+/// increase the tour weight the least, returning the increase as well.
+fn cheapest_insertion<R: Ring>(
+    problem: &GtspProblem<R>,
+    tour: &[usize],
+    candidates: impl Iterator<Item = usize>,
+) -> (R, usize, usize) {
+    candidates
+        .cartesian_product(0..tour.len())
+        .map(|(v, i)| {
+            let a = tour[i];
+            let b = tour[(i + 1) % tour.len()];
+            let increase = problem.dist(a, v) + problem.dist(v, b) - problem.dist(a, b);
+            (increase, i + 1, v)
+        })
+        .min_by_key(|&(increase, _, _)| increase)
+        .expect("no candidates")
+}
@@ -0,0 +1,114 @@
+use std::{collections::VecDeque, marker::PhantomData};
+
+use crate::{
+    gtsp::{
+        neighborhoods::{CandidateNeighborhood, Edge},
+        GtspProblem, Solution,
+    },
+    termination::Termination,
+    ImprovementHeuristic, Move, Problem, Ring, TabuNeighborhood,
+};
+
+/// Local search with don't-look bits over the candidate moves of `N` (see `Candidates`): only
+/// clusters whose arcs changed recently are examined.
+///
+/// Initially every cluster is active. An active cluster is examined by generating the moves of
+/// `N` between its position and the positions of its nearest clusters (see
+/// `GtspProblem::nearest_clusters`) and applying the best improving one. Applying a move activates
+/// the clusters of the endpoints of all removed and added edges; a cluster without an improving
+/// move becomes inactive. The search stops when no cluster is active.
+///
+/// Examining a cluster takes time proportional to the number of nearest clusters. Applying a move
+/// copies the tour, and the positions of the clusters and the context of `N` are recomputed along
+/// with it, so each applied move takes linear time.
+pub struct DontLookLocalSearch<N> {
+    termination: Termination,
+    _n: PhantomData<N>,
+}
+
+impl<N> DontLookLocalSearch<N> {
+    pub fn new(termination: Termination) -> Self {
+        Self {
+            termination,
+            _n: PhantomData,
+        }
+    }
+}
+
+impl<R, N> ImprovementHeuristic<GtspProblem<R>> for DontLookLocalSearch<N>
+where
+    R: Ring,
+    N: CandidateNeighborhood<R> + TabuNeighborhood<GtspProblem<R>, Attribute = Edge>,
+{
+    fn improve(
+        &mut self,
+        instance: &GtspProblem<R>,
+        current: <GtspProblem<R> as Problem>::Solution,
+    ) -> Solution<R> {
+        let mut best = current;
+        let mut position = vec![0; instance.number_of_clusters()];
+        update_positions(instance, &best, &mut position);
+        let mut context = N::context(instance, &best);
+
+        let mut active = best
+            .tour()
+            .iter()
+            .map(|&v| instance.cluster_of(v))
+            .collect::<VecDeque<_>>();
+        let mut is_active = vec![true; instance.number_of_clusters()];
+
+        while !self.termination.should_terminate() {
+            let Some(c) = active.pop_front() else {
+                break;
+            };
+            is_active[c] = false;
+
+            let i = position[c];
+            let mut moves = Vec::new();
+            for &d in instance.nearest_clusters(c) {
+                N::candidate_moves(instance, &best, &context, i, position[d], &mut moves);
+            }
+            let Some(improving_move) = moves
+                .into_iter()
+                .max_by_key(|m| m.score_increase())
+                .filter(|m| m.is_improving())
+            else {
+                continue;
+            };
+
+            let changed_edges = N::removed_attributes(&improving_move)
+                .into_iter()
+                .chain(N::added_attributes(&improving_move))
+                .collect::<Vec<_>>();
+            best = improving_move.into_solution();
+            update_positions(instance, &best, &mut position);
+            context = N::context(instance, &best);
+
+            for c in changed_edges
+                .into_iter()
+                .flat_map(|(u, v)| [u, v])
+                .map(|v| instance.cluster_of(v))
+                .chain([c])
+            {
+                if !is_active[c] {
+                    is_active[c] = true;
+                    active.push_back(c);
+                }
+            }
+
+            self.termination.iteration();
+        }
+
+        best
+    }
+}
+
+fn update_positions<R: Ring>(
+    instance: &GtspProblem<R>,
+    solution: &Solution<R>,
+    position: &mut [usize],
+) {
+    for (k, &v) in solution.tour().iter().enumerate() {
+        position[instance.cluster_of(v)] = k;
+    }
+}
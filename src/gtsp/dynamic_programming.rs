@@ -0,0 +1,155 @@
+use std::mem;
+
+use anyhow::bail;
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    MetaHeuristic, Ring,
+};
+
+/// Exact solver using the Held-Karp dynamic program over subsets of clusters. The tour starts in
+/// the smallest cluster, and for every vertex `v` and set `S` of visited clusters, the table holds
+/// the length of the shortest path from the start vertex through all clusters in `S` ending in
+/// `v`. This needs `O(2^m * n)` memory and `O(2^m * n^2)` time per vertex of the smallest
+/// cluster, so it is only feasible for small instances.
+pub struct DynamicProgramming {
+    max_memory: usize,
+}
+
+impl DynamicProgramming {
+    pub const MAX_CLUSTERS: usize = 32;
+
+    pub fn new() -> Self {
+        Self {
+            max_memory: 1 << 30,
+        }
+    }
+
+    /// Sets the maximum size of the table in bytes.
+    pub fn with_max_memory(max_memory: usize) -> Self {
+        Self { max_memory }
+    }
+
+    pub fn required_memory<R>(instance: &GtspProblem<R>) -> Option<usize> {
+        let m = instance.clusters.len();
+        if !(2..=Self::MAX_CLUSTERS).contains(&m) {
+            return None;
+        }
+        let smallest = instance.clusters.iter().map(Vec::len).min().unwrap();
+        1usize
+            .checked_shl(m as u32 - 1)?
+            .checked_mul(instance.number_of_vertices - smallest)?
+            .checked_mul(mem::size_of::<Option<R>>())
+    }
+
+    /// Checks whether the instance is small enough to be solved.
+    pub fn check<R>(&self, instance: &GtspProblem<R>) -> anyhow::Result<()> {
+        if instance.clusters.len() < 2 {
+            bail!("at least two clusters are needed");
+        }
+        if instance.clusters.len() > Self::MAX_CLUSTERS {
+            bail!(
+                "{} clusters exceed the maximum of {}",
+                instance.clusters.len(),
+                Self::MAX_CLUSTERS
+            );
+        }
+        match Self::required_memory(instance) {
+            Some(required) if required <= self.max_memory => Ok(()),
+            _ => bail!(
+                "the table would exceed the memory limit of {} bytes",
+                self.max_memory
+            ),
+        }
+    }
+}
+
+impl Default for DynamicProgramming {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Ring> MetaHeuristic<GtspProblem<R>> for DynamicProgramming {
+    /// Panics if the instance does not pass `DynamicProgramming::check`.
+    fn run(self, instance: &GtspProblem<R>) -> Solution<R> {
+        self.check(instance).expect("instance too large");
+
+        let start_cluster = (0..instance.clusters.len())
+            .min_by_key(|&c| instance.clusters[c].len())
+            .unwrap();
+        let other_clusters = (0..instance.clusters.len())
+            .filter(|&c| c != start_cluster)
+            .collect::<Vec<_>>();
+
+        // All vertices outside of the start cluster, grouped by cluster.
+        let vertices = other_clusters
+            .iter()
+            .flat_map(|&c| instance.clusters[c].iter().copied())
+            .collect::<Vec<_>>();
+        let bit_of = |v: usize| {
+            1usize
+                << other_clusters
+                    .iter()
+                    .position(|&c| c == instance.cluster_of(v))
+                    .unwrap()
+        };
+        let bits = vertices.iter().map(|&v| bit_of(v)).collect::<Vec<_>>();
+
+        let k = vertices.len();
+        let full = (1usize << other_clusters.len()) - 1;
+        let mut table: Vec<Option<R>> = vec![None; (full + 1) * k];
+
+        instance.clusters[start_cluster]
+            .iter()
+            .map(|&s| {
+                table.fill(None);
+                for (i, &v) in vertices.iter().enumerate() {
+                    table[bits[i] * k + i] = Some(instance.dist(s, v));
+                }
+
+                for mask in 1..=full {
+                    for (i, &v) in vertices.iter().enumerate() {
+                        if mask & bits[i] != 0 {
+                            continue;
+                        }
+                        table[(mask | bits[i]) * k + i] = vertices
+                            .iter()
+                            .enumerate()
+                            .filter_map(|(j, &u)| Some(table[mask * k + j]? + instance.dist(u, v)))
+                            .min();
+                    }
+                }
+
+                let (weight, last) = vertices
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, &v)| Some((table[full * k + i]? + instance.dist(v, s), i)))
+                    .min()
+                    .unwrap();
+
+                // Backtrack by searching for a predecessor that is consistent with the table.
+                let mut tour = vec![vertices[last]];
+                let (mut mask, mut i) = (full, last);
+                while mask != bits[i] {
+                    let length = table[mask * k + i].unwrap();
+                    let previous_mask = mask & !bits[i];
+                    i = (0..k)
+                        .find(|&j| {
+                            table[previous_mask * k + j].is_some_and(|d| {
+                                d + instance.dist(vertices[j], vertices[i]) == length
+                            })
+                        })
+                        .unwrap();
+                    mask = previous_mask;
+                    tour.push(vertices[i]);
+                }
+                tour.push(s);
+                tour.reverse();
+
+                Solution::new(instance, tour).assert_weight(weight)
+            })
+            .min_by_key(|s| s.weight())
+            .unwrap()
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    termination::Termination,
+    FromF64, ImprovementHeuristic, Problem, Ring, ToF64,
+};
+
+/// The penalty of an arc per time it was penalized, relative to the average arc weight of the
+/// first local optimum (Voudouris and Tsang, 1999).
+const LAMBDA_FACTOR: f64 = 0.3;
+
+/// Guided local search: the local search `L`, usually a `LocalSearch`, is run on a penalized view
+/// of the problem (see `GtspProblem::penalized`). At every local optimum, the arcs of the tour
+/// with the largest utility, their weight divided by one plus the number of times they were
+/// penalized, are penalized once more, and the local search continues from there. The local
+/// search stops by its own termination, so its moves only count towards the budget of `L`; every
+/// penalization counts as an iteration of the guided local search. The best tour by the original
+/// weights is returned.
+pub struct GuidedLocalSearch<L> {
+    termination: Termination,
+    local_search: L,
+}
+
+impl<L> GuidedLocalSearch<L> {
+    pub fn new(termination: Termination, local_search: L) -> Self {
+        Self {
+            termination,
+            local_search,
+        }
+    }
+}
+
+impl<R, L> ImprovementHeuristic<GtspProblem<R>> for GuidedLocalSearch<L>
+where
+    R: Ring + ToF64 + FromF64,
+    L: ImprovementHeuristic<GtspProblem<R>>,
+{
+    fn improve(
+        &mut self,
+        instance: &GtspProblem<R>,
+        current: <GtspProblem<R> as Problem>::Solution,
+    ) -> Solution<R> {
+        let n = current.tour().len();
+        let mut best = current.clone();
+        let mut view = instance.penalized();
+        let mut solution = Solution::new(&view, current.tour().to_owned());
+        let mut counts = HashMap::<(usize, usize), usize>::new();
+        let mut lambda = None;
+
+        while !self.termination.should_terminate() {
+            solution = self.local_search.improve(&view, solution);
+            let tour = solution.tour();
+
+            let original = Solution::new(instance, tour.to_owned());
+            if original.weight() < best.weight() {
+                best = original;
+            }
+            let lambda = *lambda.get_or_insert_with(|| {
+                R::from_f64((LAMBDA_FACTOR * best.weight().to_f64() / n as f64).max(1.0))
+            });
+
+            let arc = |k: usize| {
+                let (u, v) = (tour[k], tour[(k + 1) % n]);
+                if instance.is_symmetric() {
+                    (u.min(v), u.max(v))
+                } else {
+                    (u, v)
+                }
+            };
+            let utility = |k: usize| {
+                let (u, v) = (tour[k], tour[(k + 1) % n]);
+                let count = counts.get(&arc(k)).copied().unwrap_or(0);
+                instance.base_dist(u, v).to_f64() / (1 + count) as f64
+            };
+            let max_utility = (0..n).map(utility).fold(f64::NEG_INFINITY, f64::max);
+            let penalized = (0..n)
+                .filter(|&k| utility(k) == max_utility)
+                .map(arc)
+                .collect::<Vec<_>>();
+            for (u, v) in penalized {
+                *counts.entry((u, v)).or_default() += 1;
+                view.add_penalty(u, v, lambda);
+                if instance.is_symmetric() {
+                    view.add_penalty(v, u, lambda);
+                }
+            }
+            solution = Solution::new(&view, solution.tour().to_owned());
+
+            self.termination.iteration();
+        }
+
+        best
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    termination::Termination,
+    ImprovementHeuristic, Problem, Ring,
+};
+
+pub const DEFAULT_MAX_DEPTH: usize = 50;
+
+/// Lin–Kernighan style variable-depth search adapted to the GTSP.
+///
+/// Every chain removes one arc of the tour and repeatedly turns the resulting Hamiltonian path
+/// into another one: the last vertex gets an arc to a cluster from its neighbor list (see
+/// `GtspProblem::neighbors`), whose vertex is re-selected for its new neighbors, and the path
+/// behind that cluster is reversed. After every step the path is closed with the best vertex of
+/// the last cluster. A chain continues while the path is shorter than the best tour so far, touches
+/// every cluster at most once and has at most `max_depth` steps. The best closed tour of the
+/// chain is applied if it is an improvement.
+pub struct LinKernighan {
+    termination: Termination,
+    max_depth: usize,
+}
+
+impl LinKernighan {
+    pub fn new(termination: Termination) -> Self {
+        Self::with_max_depth(termination, DEFAULT_MAX_DEPTH)
+    }
+
+    pub fn with_max_depth(termination: Termination, max_depth: usize) -> Self {
+        Self {
+            termination,
+            max_depth,
+        }
+    }
+
+    /// Runs a chain starting by removing the arc after position `i`. Returns the weight and the
+    /// tour of the best improvement if one is found.
+    fn chain<R: Ring>(
+        &self,
+        problem: &GtspProblem<R>,
+        current: &Solution<R>,
+        i: usize,
+    ) -> Option<(R, Vec<usize>)> {
+        let tour = current.tour();
+        let n = tour.len();
+        let cluster = |v: usize| problem.clusters()[problem.cluster_of(v)].iter().copied();
+
+        let mut path = (1..=n).map(|k| tour[(i + k) % n]).collect::<Vec<_>>();
+        let a = path[0];
+        let mut path_cost = current.weight() - problem.dist(path[n - 1], a);
+        let mut best_weight = current.weight();
+        let mut best_tour = None;
+
+        let mut position = vec![0; problem.number_of_clusters()];
+        let mut touched = vec![false; problem.number_of_clusters()];
+
+        for _ in 0..self.max_depth {
+            for (k, &v) in path.iter().enumerate() {
+                position[problem.cluster_of(v)] = k;
+            }
+            let b = path[n - 1];
+            let step = problem
+                .neighbors(b)
+                .iter()
+                .filter_map(|&c| {
+                    let j = position[problem.cluster_of(c)];
+                    if j == 0
+                        || j + 2 >= n
+                        || touched[problem.cluster_of(path[j])]
+                        || touched[problem.cluster_of(path[j + 1])]
+                    {
+                        return None;
+                    }
+                    let (prev, x, next) = (path[j - 1], path[j], path[j + 1]);
+                    let (added_cost, v) = cluster(x)
+                        .map(|v| (problem.dist(prev, v) + problem.dist(v, b), v))
+                        .min()?;
+                    let mut cost =
+                        path_cost + added_cost - problem.dist(prev, x) - problem.dist(x, next);
+                    if !problem.is_symmetric() {
+                        cost = cost + reversal_cost(problem, &path[j + 1..]);
+                    }
+                    // The gain criterion: the path has to stay shorter than the best tour.
+                    (cost < best_weight).then_some((cost, j, v))
+                })
+                .min();
+            let Some((cost, j, v)) = step else {
+                break;
+            };
+
+            touched[problem.cluster_of(path[j])] = true;
+            touched[problem.cluster_of(path[j + 1])] = true;
+            path[j] = v;
+            path[j + 1..].reverse();
+            path_cost = cost;
+
+            let (prev, e) = (path[n - 2], path[n - 1]);
+            let (closing_cost, v) = cluster(e)
+                .map(|v| (problem.dist(prev, v) + problem.dist(v, a), v))
+                .min()
+                .unwrap();
+            let weight = path_cost - problem.dist(prev, e) + closing_cost;
+            if weight < best_weight {
+                best_weight = weight;
+                let mut tour = path.clone();
+                tour[n - 1] = v;
+                best_tour = Some((weight, tour));
+            }
+        }
+
+        best_tour
+    }
+}
+
+/// How much the cost of a path changes if it is reversed.
+fn reversal_cost<R: Ring>(problem: &GtspProblem<R>, path: &[usize]) -> R {
+    path.windows(2)
+        .map(|w| problem.dist(w[1], w[0]) - problem.dist(w[0], w[1]))
+        .sum()
+}
+
+impl<R: Ring> ImprovementHeuristic<GtspProblem<R>> for LinKernighan {
+    fn improve(
+        &mut self,
+        instance: &GtspProblem<R>,
+        current: <GtspProblem<R> as Problem>::Solution,
+    ) -> Solution<R> {
+        let mut best = current;
+        let n = best.tour().len();
+        if n < 4 {
+            return best;
+        }
+
+        // Every chain start is tried once without improvement before the search stops.
+        let mut i = 0;
+        let mut unsuccessful = 0;
+        while unsuccessful < n && !self.termination.should_terminate() {
+            match self.chain(instance, &best, i) {
+                Some((weight, tour)) => {
+                    best = Solution::new(instance, tour).assert_weight(weight);
+                    unsuccessful = 0;
+                    self.termination.iteration();
+                }
+                None => unsuccessful += 1,
+            }
+            i = (i + 1) % n;
+        }
+
+        best
+    }
+}
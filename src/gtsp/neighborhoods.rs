@@ -1,8 +1,79 @@
+use rand::{seq::SliceRandom as _, Rng};
+
+pub mod candidates;
+pub use candidates::{CandidateNeighborhood, Candidates};
+
 pub mod two_opt;
 pub use two_opt::TwoOptNeighborhood;
 
 pub mod swap;
 pub use swap::SwapNeighborhood;
 
+pub mod cluster_swap;
+pub use cluster_swap::ClusterSwapNeighborhood;
+
 pub mod inserts;
 pub use inserts::InsertsNeighborhood;
+
+pub mod or_opt;
+pub use or_opt::OrOptNeighborhood;
+
+pub mod three_opt;
+pub use three_opt::{Or3OptNeighborhood, ThreeOptNeighborhood};
+
+/// An undirected edge between two vertices, used as the attribute of moves in tabu lists.
+pub type Edge = (usize, usize);
+
+fn edge(u: usize, v: usize) -> Edge {
+    (u.min(v), u.max(v))
+}
+
+/// The order in which the move iterators visit the positions of the tour, and how far the moves of
+/// every position are rotated.
+/// Two distinct positions of a tour with `n` positions, drawn uniformly and in order.
+fn random_pair(n: usize, rng: &mut impl Rng) -> (usize, usize) {
+    let i = rng.gen_range(0..n);
+    let j = (i + rng.gen_range(1..n)) % n;
+    (i.min(j), i.max(j))
+}
+
+struct ScanOrder {
+    positions: Option<Vec<usize>>,
+    offset: usize,
+}
+
+impl ScanOrder {
+    fn sequential() -> Self {
+        Self {
+            positions: None,
+            offset: 0,
+        }
+    }
+
+    /// The positions in random order, with a random offset.
+    fn random(n: usize, rng: &mut impl Rng) -> Self {
+        let mut positions = (0..n).collect::<Vec<_>>();
+        positions.shuffle(rng);
+        Self {
+            positions: Some(positions),
+            offset: rng.gen(),
+        }
+    }
+
+    /// The position visited in step `k`.
+    fn position(&self, k: usize) -> usize {
+        self.positions.as_ref().map_or(k, |positions| positions[k])
+    }
+
+    /// The index of the `t`th move of a position with `len` moves.
+    fn inner(&self, t: usize, len: usize) -> usize {
+        (t + self.offset % len) % len
+    }
+
+    /// Rotates the moves of a position generated at once by the offset.
+    fn rotate<T>(&self, moves: &mut [T]) {
+        if !moves.is_empty() {
+            moves.rotate_left(self.offset % moves.len());
+        }
+    }
+}
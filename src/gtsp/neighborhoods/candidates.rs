@@ -0,0 +1,146 @@
+use std::marker::PhantomData;
+
+use rand::Rng;
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    MoveNeighborhood, Problem, Ring, TabuNeighborhood,
+};
+
+use super::ScanOrder;
+
+/// A neighborhood whose moves can be generated for a position of the tour and a position of a
+/// nearby cluster.
+pub trait CandidateNeighborhood<R: Ring>: MoveNeighborhood<GtspProblem<R>> {
+    /// What the moves need to know about the current solution, computed once per solution.
+    type Context;
+
+    fn context(problem: &GtspProblem<R>, current: &Solution<R>) -> Self::Context;
+
+    /// Adds the moves that make the cluster at position `p` a neighbor of the cluster at position
+    /// `i` to `moves`.
+    fn candidate_moves<'c>(
+        problem: &'c GtspProblem<R>,
+        current: &'c Solution<R>,
+        context: &Self::Context,
+        i: usize,
+        p: usize,
+        moves: &mut Vec<Self::Move<'c>>,
+    ) where
+        R: 'c;
+}
+
+/// Restricts a neighborhood to the moves between nearby clusters (see
+/// `GtspProblem::nearest_clusters`), so that there are O(n) instead of O(n²) moves.
+pub struct Candidates<N>(PhantomData<N>);
+
+impl<R: Ring, N: CandidateNeighborhood<R>> MoveNeighborhood<GtspProblem<R>> for Candidates<N> {
+    type Move<'c> = N::Move<'c> where R: 'c;
+
+    type Iter<'c> = CandidateMoves<'c, R, N> where R: 'c;
+
+    fn moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+    ) -> Self::Iter<'c> {
+        CandidateMoves::new(problem, current, ScanOrder::sequential())
+    }
+
+    fn random_moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Self::Iter<'c> {
+        CandidateMoves::new(
+            problem,
+            current,
+            ScanOrder::random(current.tour().len(), rng),
+        )
+    }
+}
+
+impl<'p, R: Ring, N: CandidateNeighborhood<R>> CandidateMoves<'p, R, N> {
+    fn new(problem: &'p GtspProblem<R>, current: &'p Solution<R>, order: ScanOrder) -> Self {
+        let mut position = vec![0; problem.number_of_clusters()];
+        for (k, &v) in current.tour().iter().enumerate() {
+            position[problem.cluster_of(v)] = k;
+        }
+        CandidateMoves {
+            problem,
+            current,
+            position,
+            context: N::context(problem, current),
+            order,
+            done: 0,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl<R: Ring, N> TabuNeighborhood<GtspProblem<R>> for Candidates<N>
+where
+    N: CandidateNeighborhood<R> + TabuNeighborhood<GtspProblem<R>>,
+{
+    type Attribute = N::Attribute;
+    type Attributes = N::Attributes;
+
+    fn removed_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        N::removed_attributes(m)
+    }
+
+    fn added_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        N::added_attributes(m)
+    }
+}
+
+/// Generates the moves position by position, for the positions of the nearest clusters.
+pub struct CandidateMoves<'p, R: Ring, N: CandidateNeighborhood<R>>
+where
+    R: 'p,
+{
+    problem: &'p GtspProblem<R>,
+    current: &'p Solution<R>,
+    /// The position of every cluster in the tour.
+    position: Vec<usize>,
+    context: N::Context,
+    order: ScanOrder,
+    /// The number of positions `i` whose moves were generated.
+    done: usize,
+    buffer: Vec<N::Move<'p>>,
+}
+
+impl<'p, R: Ring, N: CandidateNeighborhood<R>> Iterator for CandidateMoves<'p, R, N> {
+    type Item = N::Move<'p>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(m) = self.buffer.pop() {
+                return Some(m);
+            }
+            let tour = self.current.tour();
+            if self.done >= tour.len() {
+                return None;
+            }
+            let i = self.order.position(self.done);
+            let cluster = self.problem.cluster_of(tour[i]);
+            for &c in self.problem.nearest_clusters(cluster) {
+                N::candidate_moves(
+                    self.problem,
+                    self.current,
+                    &self.context,
+                    i,
+                    self.position[c],
+                    &mut self.buffer,
+                );
+            }
+            self.order.rotate(&mut self.buffer);
+            self.done += 1;
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use itertools::Itertools as _;
+use rand::Rng;
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    Move, MoveNeighborhood, Problem, Ring, TabuNeighborhood,
+};
+
+use super::{edge, random_pair, CandidateNeighborhood, Edge, ScanOrder};
+
+/// Exchanges the positions of two clusters and selects the best vertex of each cluster for its
+/// new neighbors, as in the "swaps" neighborhood of Gutin and Karapetyan (2009).
+pub struct ClusterSwapNeighborhood;
+
+pub struct ClusterSwapMove<'p, R> {
+    problem: &'p GtspProblem<R>,
+    current: &'p Solution<R>,
+    i: usize,
+    j: usize,
+    /// The new vertex at position `i`, of the cluster previously at `j`.
+    at_i: usize,
+    /// The new vertex at position `j`, of the cluster previously at `i`.
+    at_j: usize,
+    score_increase: R,
+}
+
+impl<'p, R: Ring> ClusterSwapMove<'p, R> {
+    fn new(problem: &'p GtspProblem<R>, current: &'p Solution<R>, i: usize, j: usize) -> Self {
+        let n = current.tour().len();
+        let t = |k: usize| current.tour()[k % n];
+        let cluster = |v: usize| problem.clusters()[problem.cluster_of(v)].iter().copied();
+
+        // Adjacent positions `p` and `q = p + 1` have to be selected together.
+        let adjacent = if j == i + 1 {
+            Some((i, j))
+        } else if i == 0 && j == n - 1 {
+            Some((j, i))
+        } else {
+            None
+        };
+        let (at_i, at_j) = match adjacent {
+            Some((p, q)) => {
+                let (before, after) = (t(p + n - 1), t(q + 1));
+                let (_, at_p, at_q) = cluster(t(q))
+                    .cartesian_product(cluster(t(p)))
+                    .map(|(a, b)| {
+                        (
+                            problem.dist(before, a) + problem.dist(a, b) + problem.dist(b, after),
+                            a,
+                            b,
+                        )
+                    })
+                    .min()
+                    .unwrap();
+                if p == i {
+                    (at_p, at_q)
+                } else {
+                    (at_q, at_p)
+                }
+            }
+            None => {
+                let best = |v: usize, before: usize, after: usize| {
+                    cluster(v)
+                        .min_by_key(|&x| problem.dist(before, x) + problem.dist(x, after))
+                        .unwrap()
+                };
+                (
+                    best(t(j), t(i + n - 1), t(i + 1)),
+                    best(t(i), t(j + n - 1), t(j + 1)),
+                )
+            }
+        };
+
+        let mut this = Self {
+            problem,
+            current,
+            i,
+            j,
+            at_i,
+            at_j,
+            score_increase: 0.into(),
+        };
+        let changed_arcs = this.changed_arcs();
+        this.score_increase = (0..changed_arcs.len())
+            .filter(|&a| !changed_arcs[..a].contains(&changed_arcs[a]))
+            .map(|a| {
+                let k = changed_arcs[a];
+                current.arc_cost(problem, k, k + 1)
+                    - problem.dist(this.swapped(k), this.swapped(k + 1))
+            })
+            .sum();
+        this
+    }
+
+    /// The arcs starting right before or at one of the swapped positions, which are the only ones
+    /// that change.
+    fn changed_arcs(&self) -> [usize; 4] {
+        let n = self.current.tour().len();
+        [self.i + n - 1, self.i, self.j + n - 1, self.j].map(|k| k % n)
+    }
+
+    fn swapped(&self, k: usize) -> usize {
+        let tour = self.current.tour();
+        let k = k % tour.len();
+        if k == self.i {
+            self.at_i
+        } else if k == self.j {
+            self.at_j
+        } else {
+            tour[k]
+        }
+    }
+}
+
+impl<'p, R: Ring> Move<GtspProblem<R>> for ClusterSwapMove<'p, R> {
+    fn score_increase(&self) -> <GtspProblem<R> as Problem>::Score {
+        self.score_increase
+    }
+
+    fn is_improving(&self) -> bool {
+        self.score_increase() > 0.into()
+    }
+
+    fn into_solution(self) -> <GtspProblem<R> as Problem>::Solution {
+        let mut tour = self.current.tour().to_owned();
+        tour[self.i] = self.at_i;
+        tour[self.j] = self.at_j;
+        Solution::new(self.problem, tour).assert_weight(self.current.weight - self.score_increase)
+    }
+}
+
+impl<R: Ring> MoveNeighborhood<GtspProblem<R>> for ClusterSwapNeighborhood {
+    type Move<'c> = ClusterSwapMove<'c , R> where R: 'c;
+
+    type Iter<'c> = ClusterSwapMoves<'c, R> where R: 'c;
+
+    fn moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+    ) -> Self::Iter<'c> {
+        ClusterSwapMoves {
+            problem,
+            current,
+            order: ScanOrder::sequential(),
+            done: 0,
+            t: 0,
+        }
+    }
+
+    fn random_moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Self::Iter<'c> {
+        ClusterSwapMoves {
+            problem,
+            current,
+            order: ScanOrder::random(current.tour().len(), rng),
+            done: 0,
+            t: 0,
+        }
+    }
+
+    fn random_move<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Option<Self::Move<'c>> {
+        let n = current.tour().len();
+        if n < 3 {
+            return None;
+        }
+        let (i, j) = random_pair(n, rng);
+        Some(ClusterSwapMove::new(problem, current, i, j))
+    }
+}
+
+impl<R: Ring> TabuNeighborhood<GtspProblem<R>> for ClusterSwapNeighborhood {
+    type Attribute = Edge;
+    type Attributes = [Edge; 4];
+
+    fn removed_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        let t = |k: usize| m.current.tour()[k % m.current.tour().len()];
+        m.changed_arcs().map(|k| edge(t(k), t(k + 1)))
+    }
+
+    fn added_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        m.changed_arcs()
+            .map(|k| edge(m.swapped(k), m.swapped(k + 1)))
+    }
+}
+
+impl<R: Ring> CandidateNeighborhood<R> for ClusterSwapNeighborhood {
+    type Context = ();
+
+    fn context(_problem: &GtspProblem<R>, _current: &Solution<R>) {}
+
+    fn candidate_moves<'c>(
+        problem: &'c GtspProblem<R>,
+        current: &'c Solution<R>,
+        _context: &(),
+        i: usize,
+        p: usize,
+        moves: &mut Vec<Self::Move<'c>>,
+    ) where
+        R: 'c,
+    {
+        // Moves the cluster at `i` next to the one at `p`.
+        let n = current.tour().len();
+        if n < 3 {
+            return;
+        }
+        for j in [(p + n - 1) % n, (p + 1) % n] {
+            if j != i {
+                moves.push(ClusterSwapMove::new(problem, current, i.min(j), i.max(j)));
+            }
+        }
+    }
+}
+
+pub struct ClusterSwapMoves<'p, R> {
+    problem: &'p GtspProblem<R>,
+    current: &'p Solution<R>,
+    order: ScanOrder,
+    /// The number of positions `i` whose moves were generated.
+    done: usize,
+    /// The number of moves generated for the current position.
+    t: usize,
+}
+
+impl<'p, R: Ring> Iterator for ClusterSwapMoves<'p, R> {
+    type Item = ClusterSwapMove<'p, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.current.tour().len();
+        // With two clusters, swapping them only rotates the tour.
+        if n < 3 {
+            return None;
+        }
+        loop {
+            if self.done >= n {
+                return None;
+            }
+            // The moves of `i` swap it with every `j` in `i + 1..n`.
+            let i = self.order.position(self.done);
+            let len = n - i - 1;
+            if self.t >= len {
+                self.done += 1;
+                self.t = 0;
+                continue;
+            }
+
+            let j = i + 1 + self.order.inner(self.t, len);
+            self.t += 1;
+            return Some(ClusterSwapMove::new(self.problem, self.current, i, j));
+        }
+    }
+}
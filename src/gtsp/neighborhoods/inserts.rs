@@ -1,37 +1,231 @@
+use rand::Rng;
+
 use crate::{
     gtsp::{GtspProblem, Solution},
-    Neighborhood, Ring,
+    Move, MoveNeighborhood, Problem, Ring, TabuNeighborhood,
 };
 
+use super::{edge, CandidateNeighborhood, Edge, ScanOrder};
+
 pub struct InsertsNeighborhood;
 
-impl<R: Ring> Neighborhood<GtspProblem<R>> for InsertsNeighborhood {
-    type Iter = <Vec<Solution<R>> as IntoIterator>::IntoIter;
+/// Removes the vertex at position `i` and inserts `vertex` of the same cluster at position `j` of
+/// the remaining tour.
+pub struct InsertMove<'p, R> {
+    problem: &'p GtspProblem<R>,
+    current: &'p Solution<R>,
+    i: usize,
+    j: usize,
+    vertex: usize,
+}
+
+impl<'p, R: Ring> InsertMove<'p, R> {
+    /// The vertex at position `k` of the tour after removing position `i`.
+    fn removed(&self, k: usize) -> usize {
+        let k = k % (self.current.tour().len() - 1);
+        self.current.tour()[if k < self.i { k } else { k + 1 }]
+    }
+}
+
+impl<'p, R: Ring> Move<GtspProblem<R>> for InsertMove<'p, R> {
+    fn score_increase(&self) -> <GtspProblem<R> as Problem>::Score {
+        let n = self.current.tour().len();
+        let (p, q) = (self.i + n - 1, self.i + 1);
+        let removed_cost = self.current.forward_cost(self.problem, [p, self.i, q])
+            - self.current.arc_cost(self.problem, p, q);
+
+        let a = self.removed(self.j + n - 2);
+        let b = self.removed(self.j);
+        let added_cost = self.problem.dist(a, self.vertex) + self.problem.dist(self.vertex, b)
+            - self.problem.dist(a, b);
+
+        removed_cost - added_cost
+    }
+
+    fn is_improving(&self) -> bool {
+        self.score_increase() > 0.into()
+    }
+
+    fn into_solution(self) -> <GtspProblem<R> as Problem>::Solution {
+        let mut tour = self.current.tour().to_owned();
+        tour.remove(self.i);
+        tour.insert(self.j, self.vertex);
+        Solution::new(self.problem, tour).assert_weight(self.current.weight - self.score_increase())
+    }
+}
+
+impl<R: Ring> MoveNeighborhood<GtspProblem<R>> for InsertsNeighborhood {
+    type Move<'c> = InsertMove<'c , R> where R: 'c;
+
+    type Iter<'c> = InsertMoves<'c, R> where R: 'c;
 
-    fn neighbors_iter<'c, 'p: 'c>(
+    fn moves_iter<'c, 'p: 'c>(
         problem: &'p GtspProblem<R>,
-        current: &'c <GtspProblem<R> as crate::Problem>::Solution,
-    ) -> Self::Iter {
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+    ) -> Self::Iter<'c> {
+        InsertMoves {
+            problem,
+            current,
+            order: ScanOrder::sequential(),
+            done: 0,
+            t: 0,
+            k: 0,
+        }
+    }
+
+    fn random_moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Self::Iter<'c> {
+        InsertMoves {
+            problem,
+            current,
+            order: ScanOrder::random(current.tour().len(), rng),
+            done: 0,
+            t: 0,
+            k: 0,
+        }
+    }
+
+    fn random_move<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Option<Self::Move<'c>> {
         let n = current.tour().len();
-        let mut solutions = Vec::new();
-        for i in 0..n {
-            let cluster = problem
-                .clusters
-                .iter()
-                .position(|c| c.contains(&current.tour()[i]))
-                .unwrap();
-            for j in 0..n {
-                if i == j || i == (j + 1) % n || j == (i + 1) % n {
-                    continue;
-                }
-                for &chosen_vertex in problem.clusters[cluster].iter() {
-                    let mut tour = current.tour().to_owned();
-                    tour.remove(i);
-                    tour.insert(j, chosen_vertex);
-                    solutions.push(Solution::new(problem, tour));
-                }
+        if n < 4 {
+            return None;
+        }
+        let i = rng.gen_range(0..n);
+        let j = loop {
+            let j = rng.gen_range(0..n);
+            if !(i == j || i == (j + 1) % n || j == (i + 1) % n) {
+                break j;
             }
+        };
+        let cluster = &problem.clusters[problem.cluster_of(current.tour()[i])];
+        Some(InsertMove {
+            problem,
+            current,
+            i,
+            j,
+            vertex: cluster[rng.gen_range(0..cluster.len())],
+        })
+    }
+}
+
+impl<R: Ring> TabuNeighborhood<GtspProblem<R>> for InsertsNeighborhood {
+    type Attribute = Edge;
+    type Attributes = [Edge; 3];
+
+    fn removed_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        let n = m.current.tour().len();
+        let t = |k: usize| m.current.tour()[k % n];
+        let (a, b) = (m.removed(m.j + n - 2), m.removed(m.j));
+        [
+            edge(t(m.i + n - 1), t(m.i)),
+            edge(t(m.i), t(m.i + 1)),
+            edge(a, b),
+        ]
+    }
+
+    fn added_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        let n = m.current.tour().len();
+        let t = |k: usize| m.current.tour()[k % n];
+        let (a, b) = (m.removed(m.j + n - 2), m.removed(m.j));
+        [
+            edge(t(m.i + n - 1), t(m.i + 1)),
+            edge(a, m.vertex),
+            edge(m.vertex, b),
+        ]
+    }
+}
+
+impl<R: Ring> CandidateNeighborhood<R> for InsertsNeighborhood {
+    type Context = ();
+
+    fn context(_problem: &GtspProblem<R>, _current: &Solution<R>) {}
+
+    fn candidate_moves<'c>(
+        problem: &'c GtspProblem<R>,
+        current: &'c Solution<R>,
+        _context: &(),
+        i: usize,
+        p: usize,
+        moves: &mut Vec<Self::Move<'c>>,
+    ) where
+        R: 'c,
+    {
+        // Inserts the cluster at `i` right before or after the one at `p`.
+        let n = current.tour().len();
+        let r = if p < i { p } else { p - 1 };
+        for j in [r, r + 1] {
+            if i == j || i == (j + 1) % n || j == (i + 1) % n {
+                continue;
+            }
+            for &vertex in &problem.clusters[problem.cluster_of(current.tour()[i])] {
+                moves.push(InsertMove {
+                    problem,
+                    current,
+                    i,
+                    j,
+                    vertex,
+                });
+            }
+        }
+    }
+}
+
+pub struct InsertMoves<'p, R> {
+    problem: &'p GtspProblem<R>,
+    current: &'p Solution<R>,
+    order: ScanOrder,
+    /// The number of positions `i` whose moves were generated.
+    done: usize,
+    /// The number of positions `j` whose moves were generated for the current position `i`.
+    t: usize,
+    k: usize,
+}
+
+impl<'p, R: Ring> Iterator for InsertMoves<'p, R> {
+    type Item = InsertMove<'p, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.current.tour().len();
+        loop {
+            if self.done >= n {
+                return None;
+            }
+            if self.t >= n {
+                self.done += 1;
+                self.t = 0;
+                continue;
+            }
+            let i = self.order.position(self.done);
+            let j = self.order.inner(self.t, n);
+            let cluster = &self.problem.clusters[self.problem.cluster_of(self.current.tour()[i])];
+            if i == j || i == (j + 1) % n || j == (i + 1) % n || self.k >= cluster.len() {
+                self.t += 1;
+                self.k = 0;
+                continue;
+            }
+
+            let vertex = cluster[self.k];
+            self.k += 1;
+            return Some(InsertMove {
+                problem: self.problem,
+                current: self.current,
+                i,
+                j,
+                vertex,
+            });
         }
-        solutions.into_iter()
     }
 }
@@ -0,0 +1,328 @@
+use itertools::Itertools as _;
+use rand::Rng;
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    Move, MoveNeighborhood, Problem, Ring, TabuNeighborhood,
+};
+
+use super::{edge, CandidateNeighborhood, Edge, ScanOrder};
+
+/// Moves a segment of one to three clusters to another position of the tour, optionally reversed,
+/// and selects the best vertices for the first and last cluster of the segment given their new
+/// neighbors.
+pub struct OrOptNeighborhood;
+
+pub const MAX_SEGMENT_LENGTH: usize = 3;
+
+pub struct OrOptMove<'p, R> {
+    problem: &'p GtspProblem<R>,
+    current: &'p Solution<R>,
+    /// First position of the segment.
+    i: usize,
+    len: usize,
+    /// Offset of the vertex after which the segment is inserted, counted in the remaining tour
+    /// that starts right after the segment.
+    k: usize,
+    /// The new segment in tour order.
+    segment: [usize; MAX_SEGMENT_LENGTH],
+    score_increase: R,
+}
+
+impl<'p, R: Ring> OrOptMove<'p, R> {
+    fn new(
+        problem: &'p GtspProblem<R>,
+        current: &'p Solution<R>,
+        i: usize,
+        len: usize,
+        k: usize,
+        reversed: bool,
+    ) -> Self {
+        let n = current.tour().len();
+        let t = |k: usize| current.tour()[k % n];
+        let (a, b) = (t(i + len + k), t(i + len + k + 1));
+
+        let mut segment = [0; MAX_SEGMENT_LENGTH];
+        for (k, s) in segment[..len].iter_mut().enumerate() {
+            *s = if reversed {
+                t(i + len - 1 - k)
+            } else {
+                t(i + k)
+            };
+        }
+        let path_cost = reselect_ends(problem, a, &mut segment[..len], b);
+
+        let removed_cost =
+            current.forward_cost(problem, (i + n - 1)..=(i + n + len)) + problem.dist(a, b);
+        let added_cost = problem.dist(t(i + n - 1), t(i + len)) + path_cost;
+
+        Self {
+            problem,
+            current,
+            i,
+            len,
+            k,
+            segment,
+            score_increase: removed_cost - added_cost,
+        }
+    }
+
+    /// The vertex at position `k` of the remaining tour.
+    fn remaining(&self, k: usize) -> usize {
+        self.current.tour()[(self.i + self.len + k) % self.current.tour().len()]
+    }
+}
+
+/// Selects the best vertices for the first and last cluster of the segment between `a` and `b`,
+/// keeping the vertices in between. Returns the cost of the path from `a` to `b`.
+fn reselect_ends<R: Ring>(
+    problem: &GtspProblem<R>,
+    a: usize,
+    segment: &mut [usize],
+    b: usize,
+) -> R {
+    let cluster = |v: usize| problem.clusters[problem.cluster_of(v)].iter().copied();
+    let len = segment.len();
+    match len {
+        1 => {
+            let (cost, x) = cluster(segment[0])
+                .map(|x| (problem.dist(a, x) + problem.dist(x, b), x))
+                .min()
+                .unwrap();
+            segment[0] = x;
+            cost
+        }
+        2 => {
+            let (cost, x, y) = cluster(segment[0])
+                .cartesian_product(cluster(segment[1]))
+                .map(|(x, y)| {
+                    (
+                        problem.dist(a, x) + problem.dist(x, y) + problem.dist(y, b),
+                        x,
+                        y,
+                    )
+                })
+                .min()
+                .unwrap();
+            segment[0] = x;
+            segment[1] = y;
+            cost
+        }
+        _ => {
+            let (first_cost, x) = cluster(segment[0])
+                .map(|x| (problem.dist(a, x) + problem.dist(x, segment[1]), x))
+                .min()
+                .unwrap();
+            let (last_cost, y) = cluster(segment[len - 1])
+                .map(|y| (problem.dist(segment[len - 2], y) + problem.dist(y, b), y))
+                .min()
+                .unwrap();
+            segment[0] = x;
+            segment[len - 1] = y;
+            let inner_cost = segment[1..len - 1]
+                .iter()
+                .tuple_windows()
+                .map(|(&u, &v)| problem.dist(u, v))
+                .sum::<R>();
+            first_cost + inner_cost + last_cost
+        }
+    }
+}
+
+impl<'p, R: Ring> Move<GtspProblem<R>> for OrOptMove<'p, R> {
+    fn score_increase(&self) -> <GtspProblem<R> as Problem>::Score {
+        self.score_increase
+    }
+
+    fn is_improving(&self) -> bool {
+        self.score_increase() > 0.into()
+    }
+
+    fn into_solution(self) -> <GtspProblem<R> as Problem>::Solution {
+        let n = self.current.tour().len();
+        let tour = (0..=self.k)
+            .map(|k| self.remaining(k))
+            .chain(self.segment[..self.len].iter().copied())
+            .chain((self.k + 1..n - self.len).map(|k| self.remaining(k)))
+            .collect();
+        Solution::new(self.problem, tour).assert_weight(self.current.weight - self.score_increase)
+    }
+}
+
+impl<R: Ring> MoveNeighborhood<GtspProblem<R>> for OrOptNeighborhood {
+    type Move<'c> = OrOptMove<'c , R> where R: 'c;
+
+    type Iter<'c> = OrOptMoves<'c, R> where R: 'c;
+
+    fn moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+    ) -> Self::Iter<'c> {
+        OrOptMoves {
+            problem,
+            current,
+            order: ScanOrder::sequential(),
+            done: 0,
+            len: 1,
+            t: 0,
+            reversed: false,
+        }
+    }
+
+    fn random_moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Self::Iter<'c> {
+        OrOptMoves {
+            problem,
+            current,
+            order: ScanOrder::random(current.tour().len(), rng),
+            done: 0,
+            len: 1,
+            t: 0,
+            reversed: false,
+        }
+    }
+
+    fn random_move<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Option<Self::Move<'c>> {
+        let n = current.tour().len();
+        if n < 3 {
+            return None;
+        }
+        let len = rng.gen_range(1..=MAX_SEGMENT_LENGTH.min(n - 2));
+        Some(OrOptMove::new(
+            problem,
+            current,
+            rng.gen_range(0..n),
+            len,
+            rng.gen_range(0..n - len - 1),
+            len > 1 && rng.gen(),
+        ))
+    }
+}
+
+impl<R: Ring> TabuNeighborhood<GtspProblem<R>> for OrOptNeighborhood {
+    type Attribute = Edge;
+    type Attributes = [Edge; 3];
+
+    fn removed_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        let n = m.current.tour().len();
+        let t = |k: usize| m.current.tour()[k % n];
+        [
+            edge(t(m.i + n - 1), t(m.i)),
+            edge(t(m.i + m.len - 1), t(m.i + m.len)),
+            edge(m.remaining(m.k), m.remaining(m.k + 1)),
+        ]
+    }
+
+    fn added_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        let n = m.current.tour().len();
+        [
+            edge(m.remaining(n - m.len - 1), m.remaining(0)),
+            edge(m.remaining(m.k), m.segment[0]),
+            edge(m.segment[m.len - 1], m.remaining(m.k + 1)),
+        ]
+    }
+}
+
+impl<R: Ring> CandidateNeighborhood<R> for OrOptNeighborhood {
+    type Context = ();
+
+    fn context(_problem: &GtspProblem<R>, _current: &Solution<R>) {}
+
+    fn candidate_moves<'c>(
+        problem: &'c GtspProblem<R>,
+        current: &'c Solution<R>,
+        _context: &(),
+        i: usize,
+        p: usize,
+        moves: &mut Vec<Self::Move<'c>>,
+    ) where
+        R: 'c,
+    {
+        // Moves the segments starting at `i` right before or after the cluster at `p`.
+        let n = current.tour().len();
+        for len in 1..=MAX_SEGMENT_LENGTH.min(n.saturating_sub(2)) {
+            if (p + n - i) % n < len {
+                continue;
+            }
+            let after = (p + n - i - len) % n;
+            for k in [Some(after), after.checked_sub(1)].into_iter().flatten() {
+                if k + 1 >= n - len {
+                    continue;
+                }
+                moves.push(OrOptMove::new(problem, current, i, len, k, false));
+                if len > 1 {
+                    moves.push(OrOptMove::new(problem, current, i, len, k, true));
+                }
+            }
+        }
+    }
+}
+
+/// Enumerates the segments by their first position `i` and length, and the insertion points by
+/// the offset `k` of the vertex after which the segment is inserted in the remaining tour.
+pub struct OrOptMoves<'p, R> {
+    problem: &'p GtspProblem<R>,
+    current: &'p Solution<R>,
+    order: ScanOrder,
+    /// The number of positions `i` whose moves were generated.
+    done: usize,
+    len: usize,
+    /// The number of positions `k` whose moves were generated for the current segment.
+    t: usize,
+    reversed: bool,
+}
+
+impl<'p, R: Ring> Iterator for OrOptMoves<'p, R> {
+    type Item = OrOptMove<'p, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.current.tour().len();
+        loop {
+            if self.done >= n {
+                return None;
+            }
+            if self.len > MAX_SEGMENT_LENGTH.min(n.saturating_sub(2)) {
+                self.done += 1;
+                self.len = 1;
+                continue;
+            }
+            // Inserting after the last vertex of the remaining tour would restore the old tour.
+            let positions = n - self.len - 1;
+            if self.t >= positions {
+                self.len += 1;
+                self.t = 0;
+                continue;
+            }
+
+            let i = self.order.position(self.done);
+            let (k, reversed) = (self.order.inner(self.t, positions), self.reversed);
+            if reversed || self.len == 1 {
+                self.t += 1;
+                self.reversed = false;
+            } else {
+                self.reversed = true;
+            }
+            return Some(OrOptMove::new(
+                self.problem,
+                self.current,
+                i,
+                self.len,
+                k,
+                reversed,
+            ));
+        }
+    }
+}
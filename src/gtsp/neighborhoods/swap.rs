@@ -1,29 +1,62 @@
+use rand::Rng;
+
 use crate::{
     gtsp::{GtspProblem, Solution},
-    Move, MoveNeighborhood, Problem, Ring,
+    Move, MoveNeighborhood, Problem, Ring, TabuNeighborhood,
 };
 
+use super::{edge, random_pair, CandidateNeighborhood, Edge, ScanOrder};
+
 pub struct SwapNeighborhood;
 
 pub struct SwapMove<'p, R> {
+    problem: &'p GtspProblem<R>,
     current: &'p Solution<R>,
-    new: Solution<R>,
+    i: usize,
+    j: usize,
 }
 
 impl<'p, R: Ring> SwapMove<'p, R> {
-    pub fn new(problem: &GtspProblem<R>, current: &'p Solution<R>, i: usize, j: usize) -> Self {
-        let mut tour = current.tour.to_owned();
-        tour.swap(i, j);
+    pub fn new(problem: &'p GtspProblem<R>, current: &'p Solution<R>, i: usize, j: usize) -> Self {
         Self {
+            problem,
             current,
-            new: Solution::new(problem, tour),
+            i,
+            j,
+        }
+    }
+
+    /// The arcs starting right before or at one of the swapped positions, which are the only ones
+    /// that change.
+    fn changed_arcs(&self) -> [usize; 4] {
+        let n = self.current.tour().len();
+        [self.i + n - 1, self.i, self.j + n - 1, self.j].map(|k| k % n)
+    }
+
+    fn swapped(&self, k: usize) -> usize {
+        let tour = self.current.tour();
+        let k = k % tour.len();
+        if k == self.i {
+            tour[self.j]
+        } else if k == self.j {
+            tour[self.i]
+        } else {
+            tour[k]
         }
     }
 }
 
 impl<'p, R: Ring> Move<GtspProblem<R>> for SwapMove<'p, R> {
     fn score_increase(&self) -> <GtspProblem<R> as Problem>::Score {
-        self.current.weight() - self.new.weight()
+        let changed_arcs = self.changed_arcs();
+        (0..changed_arcs.len())
+            .filter(|&a| !changed_arcs[..a].contains(&changed_arcs[a]))
+            .map(|a| {
+                let k = changed_arcs[a];
+                self.current.arc_cost(self.problem, k, k + 1)
+                    - self.problem.dist(self.swapped(k), self.swapped(k + 1))
+            })
+            .sum()
     }
 
     fn is_improving(&self) -> bool {
@@ -31,28 +64,135 @@ impl<'p, R: Ring> Move<GtspProblem<R>> for SwapMove<'p, R> {
     }
 
     fn into_solution(self) -> <GtspProblem<R> as Problem>::Solution {
-        self.new.clone()
+        let mut tour = self.current.tour().to_owned();
+        tour.swap(self.i, self.j);
+        Solution::new(self.problem, tour).assert_weight(self.current.weight - self.score_increase())
     }
 }
 
 impl<R: Ring> MoveNeighborhood<GtspProblem<R>> for SwapNeighborhood {
     type Move<'c> = SwapMove<'c , R> where R: 'c;
 
-    // TODO: generate on the fly
-    type Iter<'c> = <Vec<Self::Move<'c>> as IntoIterator>::IntoIter where R: 'c;
+    type Iter<'c> = SwapMoves<'c, R> where R: 'c;
 
     fn moves_iter<'c, 'p: 'c>(
         problem: &'p GtspProblem<R>,
         current: &'c <GtspProblem<R> as Problem>::Solution,
     ) -> Self::Iter<'c> {
-        let mut moves = Vec::new();
+        SwapMoves {
+            problem,
+            current,
+            order: ScanOrder::sequential(),
+            done: 0,
+            t: 0,
+        }
+    }
 
-        for i in 0..current.tour().len() {
-            for j in i + 1..current.tour().len() {
-                moves.push(SwapMove::new(problem, current, i, j));
+    fn random_moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Self::Iter<'c> {
+        SwapMoves {
+            problem,
+            current,
+            order: ScanOrder::random(current.tour().len(), rng),
+            done: 0,
+            t: 0,
+        }
+    }
+
+    fn random_move<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Option<Self::Move<'c>> {
+        let n = current.tour().len();
+        if n < 2 {
+            return None;
+        }
+        let (i, j) = random_pair(n, rng);
+        Some(SwapMove::new(problem, current, i, j))
+    }
+}
+
+impl<R: Ring> TabuNeighborhood<GtspProblem<R>> for SwapNeighborhood {
+    type Attribute = Edge;
+    type Attributes = [Edge; 4];
+
+    fn removed_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        let t = |k: usize| m.current.tour()[k % m.current.tour().len()];
+        m.changed_arcs().map(|k| edge(t(k), t(k + 1)))
+    }
+
+    fn added_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        m.changed_arcs()
+            .map(|k| edge(m.swapped(k), m.swapped(k + 1)))
+    }
+}
+
+impl<R: Ring> CandidateNeighborhood<R> for SwapNeighborhood {
+    type Context = ();
+
+    fn context(_problem: &GtspProblem<R>, _current: &Solution<R>) {}
+
+    fn candidate_moves<'c>(
+        problem: &'c GtspProblem<R>,
+        current: &'c Solution<R>,
+        _context: &(),
+        i: usize,
+        p: usize,
+        moves: &mut Vec<Self::Move<'c>>,
+    ) where
+        R: 'c,
+    {
+        // Moves the cluster at `i` next to the one at `p`.
+        let n = current.tour().len();
+        for j in [(p + n - 1) % n, (p + 1) % n] {
+            if j != i {
+                moves.push(SwapMove::new(problem, current, i.min(j), i.max(j)));
             }
         }
+    }
+}
+
+pub struct SwapMoves<'p, R> {
+    problem: &'p GtspProblem<R>,
+    current: &'p Solution<R>,
+    order: ScanOrder,
+    /// The number of positions `i` whose moves were generated.
+    done: usize,
+    /// The number of moves generated for the current position.
+    t: usize,
+}
 
-        moves.into_iter()
+impl<'p, R: Ring> Iterator for SwapMoves<'p, R> {
+    type Item = SwapMove<'p, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.current.tour().len();
+        loop {
+            if self.done >= n {
+                return None;
+            }
+            // The moves of `i` swap it with every `j` in `i + 1..n`.
+            let i = self.order.position(self.done);
+            let len = n - i - 1;
+            if self.t >= len {
+                self.done += 1;
+                self.t = 0;
+                continue;
+            }
+
+            let j = i + 1 + self.order.inner(self.t, len);
+            self.t += 1;
+            return Some(SwapMove::new(self.problem, self.current, i, j));
+        }
     }
 }
@@ -0,0 +1,352 @@
+use rand::Rng;
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    Move, MoveNeighborhood, Problem, Ring, TabuNeighborhood,
+};
+
+use super::{edge, Edge, ScanOrder};
+
+/// Removes three arcs of the tour and reconnects the two segments between them in one of the four
+/// ways that replace all three arcs. Reconnections that keep one of the arcs are 2-opt moves and
+/// left to `TwoOptNeighborhood`.
+///
+/// The first new arc always starts at the vertex before the first segment and ends at a cluster
+/// close to it (see `GtspProblem::neighbors`) with a shorter arc than the removed one, so the
+/// neighborhood has O(n²) instead of O(n³) moves.
+pub struct ThreeOptNeighborhood;
+
+/// The part of `ThreeOptNeighborhood` that exchanges the two segments without reversing them, so
+/// it is just as cheap to evaluate on asymmetric instances.
+pub struct Or3OptNeighborhood;
+
+/// The order of the segments `A` and `B` after the move, where `'` means reversed.
+#[derive(Debug, Clone, Copy)]
+enum Reconnection {
+    BA,
+    ArBr,
+    BAr,
+    BrA,
+}
+
+/// A segment of the tour by the offsets of its first and last vertex from position `i`.
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    start: usize,
+    end: usize,
+    reversed: bool,
+}
+
+/// Removes the arcs after the offsets `0`, `p` and `q` from position `i`, so that the segment `A`
+/// covers the offsets `1..=p` and `B` the offsets `p + 1..=q`.
+pub struct ThreeOptMove<'p, R> {
+    problem: &'p GtspProblem<R>,
+    current: &'p Solution<R>,
+    i: usize,
+    p: usize,
+    q: usize,
+    reconnection: Reconnection,
+    score_increase: R,
+}
+
+impl<'p, R: Ring> ThreeOptMove<'p, R> {
+    fn new(
+        problem: &'p GtspProblem<R>,
+        current: &'p Solution<R>,
+        i: usize,
+        p: usize,
+        q: usize,
+        reconnection: Reconnection,
+    ) -> Self {
+        let mut this = Self {
+            problem,
+            current,
+            i,
+            p,
+            q,
+            reconnection,
+            score_increase: 0.into(),
+        };
+
+        let removed_cost = current.arc_cost(problem, i, i + 1)
+            + current.arc_cost(problem, i + p, i + p + 1)
+            + current.arc_cost(problem, i + q, i + q + 1);
+
+        let [x, y] = this.segments();
+        let mut added_cost = problem.dist(this.t(0), this.first(x))
+            + problem.dist(this.last(x), this.first(y))
+            + problem.dist(this.last(y), this.t(q + 1));
+        if !problem.is_symmetric() {
+            for s in [x, y].into_iter().filter(|s| s.reversed) {
+                added_cost = added_cost
+                    + (s.start..s.end)
+                        .map(|o| problem.dist(this.t(o + 1), this.t(o)))
+                        .sum::<R>()
+                    - current.forward_cost(problem, (i + s.start)..=(i + s.end));
+            }
+        }
+
+        this.score_increase = removed_cost - added_cost;
+        this
+    }
+
+    /// The vertex at offset `o` from position `i`.
+    fn t(&self, o: usize) -> usize {
+        let tour = self.current.tour();
+        tour[(self.i + o) % tour.len()]
+    }
+
+    /// The two segments in their new order.
+    fn segments(&self) -> [Segment; 2] {
+        let segment = |start, end, reversed| Segment {
+            start,
+            end,
+            reversed,
+        };
+        let a = |reversed| segment(1, self.p, reversed);
+        let b = |reversed| segment(self.p + 1, self.q, reversed);
+        match self.reconnection {
+            Reconnection::BA => [b(false), a(false)],
+            Reconnection::ArBr => [a(true), b(true)],
+            Reconnection::BAr => [b(false), a(true)],
+            Reconnection::BrA => [b(true), a(false)],
+        }
+    }
+
+    fn first(&self, s: Segment) -> usize {
+        self.t(if s.reversed { s.end } else { s.start })
+    }
+
+    fn last(&self, s: Segment) -> usize {
+        self.t(if s.reversed { s.start } else { s.end })
+    }
+
+    fn vertices(&self, s: Segment) -> impl Iterator<Item = usize> + '_ {
+        (s.start..=s.end).map(move |o| self.t(if s.reversed { s.start + s.end - o } else { o }))
+    }
+
+    fn removed_edges(&self) -> [Edge; 3] {
+        [
+            edge(self.t(0), self.t(1)),
+            edge(self.t(self.p), self.t(self.p + 1)),
+            edge(self.t(self.q), self.t(self.q + 1)),
+        ]
+    }
+
+    fn added_edges(&self) -> [Edge; 3] {
+        let [x, y] = self.segments();
+        [
+            edge(self.t(0), self.first(x)),
+            edge(self.last(x), self.first(y)),
+            edge(self.last(y), self.t(self.q + 1)),
+        ]
+    }
+}
+
+impl<'p, R: Ring> Move<GtspProblem<R>> for ThreeOptMove<'p, R> {
+    fn score_increase(&self) -> <GtspProblem<R> as Problem>::Score {
+        self.score_increase
+    }
+
+    fn is_improving(&self) -> bool {
+        self.score_increase() > 0.into()
+    }
+
+    fn into_solution(self) -> <GtspProblem<R> as Problem>::Solution {
+        let n = self.current.tour().len();
+        let [x, y] = self.segments();
+        let tour = std::iter::once(self.t(0))
+            .chain(self.vertices(x))
+            .chain(self.vertices(y))
+            .chain((self.q + 1..n).map(|o| self.t(o)))
+            .collect();
+        Solution::new(self.problem, tour).assert_weight(self.current.weight - self.score_increase)
+    }
+}
+
+impl<R: Ring> MoveNeighborhood<GtspProblem<R>> for ThreeOptNeighborhood {
+    type Move<'c> = ThreeOptMove<'c , R> where R: 'c;
+
+    type Iter<'c> = ThreeOptMoves<'c, R> where R: 'c;
+
+    fn moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+    ) -> Self::Iter<'c> {
+        ThreeOptMoves::new(problem, current, true, ScanOrder::sequential())
+    }
+
+    fn random_moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Self::Iter<'c> {
+        let order = ScanOrder::random(current.tour().len(), rng);
+        ThreeOptMoves::new(problem, current, true, order)
+    }
+}
+
+impl<R: Ring> MoveNeighborhood<GtspProblem<R>> for Or3OptNeighborhood {
+    type Move<'c> = ThreeOptMove<'c , R> where R: 'c;
+
+    type Iter<'c> = ThreeOptMoves<'c, R> where R: 'c;
+
+    fn moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+    ) -> Self::Iter<'c> {
+        ThreeOptMoves::new(problem, current, false, ScanOrder::sequential())
+    }
+
+    fn random_moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Self::Iter<'c> {
+        let order = ScanOrder::random(current.tour().len(), rng);
+        ThreeOptMoves::new(problem, current, false, order)
+    }
+}
+
+impl<R: Ring> TabuNeighborhood<GtspProblem<R>> for ThreeOptNeighborhood {
+    type Attribute = Edge;
+    type Attributes = [Edge; 3];
+
+    fn removed_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        m.removed_edges()
+    }
+
+    fn added_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        m.added_edges()
+    }
+}
+
+impl<R: Ring> TabuNeighborhood<GtspProblem<R>> for Or3OptNeighborhood {
+    type Attribute = Edge;
+    type Attributes = [Edge; 3];
+
+    fn removed_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        m.removed_edges()
+    }
+
+    fn added_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        m.added_edges()
+    }
+}
+
+/// Generates the moves position by position: for every candidate end of the first new arc, the
+/// third removed arc is enumerated exhaustively.
+pub struct ThreeOptMoves<'p, R> {
+    problem: &'p GtspProblem<R>,
+    current: &'p Solution<R>,
+    reversals: bool,
+    /// The position of every cluster in the tour.
+    position: Vec<usize>,
+    order: ScanOrder,
+    /// The number of positions `i` whose moves were generated.
+    done: usize,
+    buffer: Vec<ThreeOptMove<'p, R>>,
+}
+
+impl<'p, R: Ring> ThreeOptMoves<'p, R> {
+    fn new(
+        problem: &'p GtspProblem<R>,
+        current: &'p Solution<R>,
+        reversals: bool,
+        order: ScanOrder,
+    ) -> Self {
+        let mut position = vec![0; problem.number_of_clusters()];
+        for (k, &v) in current.tour().iter().enumerate() {
+            position[problem.cluster_of(v)] = k;
+        }
+        Self {
+            problem,
+            current,
+            reversals,
+            position,
+            order,
+            done: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    fn fill_buffer(&mut self, i: usize) {
+        let (problem, current) = (self.problem, self.current);
+        let tour = current.tour();
+        let n = tour.len();
+        if n < 3 {
+            return;
+        }
+        let removed_cost = current.arc_cost(problem, i, i + 1);
+        let neighbors = problem.neighbors(tour[i]);
+
+        for (k, &c) in neighbors.iter().enumerate() {
+            let cluster = problem.cluster_of(c);
+            if neighbors[..k]
+                .iter()
+                .any(|&d| problem.cluster_of(d) == cluster)
+            {
+                continue;
+            }
+            let o = (self.position[cluster] + n - i) % n;
+            if problem.dist(tour[i], tour[self.position[cluster]]) >= removed_cost {
+                continue;
+            }
+
+            let mut push = |p, q, reconnection| {
+                self.buffer
+                    .push(ThreeOptMove::new(problem, current, i, p, q, reconnection))
+            };
+            // The candidate starts B.
+            if o >= 2 {
+                for q in o..n {
+                    push(o - 1, q, Reconnection::BA);
+                    if self.reversals {
+                        push(o - 1, q, Reconnection::BAr);
+                    }
+                }
+            }
+            if self.reversals {
+                // The candidate ends A.
+                for q in o + 1..n {
+                    push(o, q, Reconnection::ArBr);
+                }
+                // The candidate ends B.
+                for p in 1..o {
+                    push(p, o, Reconnection::BrA);
+                }
+            }
+        }
+    }
+}
+
+impl<'p, R: Ring> Iterator for ThreeOptMoves<'p, R> {
+    type Item = ThreeOptMove<'p, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(m) = self.buffer.pop() {
+                return Some(m);
+            }
+            let n = self.current.tour().len();
+            if self.done >= n {
+                return None;
+            }
+            self.fill_buffer(self.order.position(self.done));
+            self.order.rotate(&mut self.buffer);
+            self.done += 1;
+        }
+    }
+}
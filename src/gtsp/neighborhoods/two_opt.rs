@@ -1,29 +1,52 @@
-use std::iter;
+use rand::Rng;
 
 use crate::{
     gtsp::{GtspProblem, Solution},
-    Move, MoveNeighborhood, Problem, Ring,
+    Move, MoveNeighborhood, Problem, Ring, TabuNeighborhood,
 };
 
+use super::{edge, random_pair, CandidateNeighborhood, Edge, ScanOrder};
+
 pub struct TwoOptNeighborhood;
 
+/// Reverses the segment between the positions `i + 1` and `h`.
 pub struct TwoOptMove<'p, R> {
     problem: &'p GtspProblem<R>,
     current: &'p Solution<R>,
     i: usize,
     h: usize,
+    score_increase: R,
+}
+
+impl<'p, R: Ring> TwoOptMove<'p, R> {
+    /// `reversal_cost` is how much the cost of the reversed segment changes, which is zero on
+    /// symmetric instances.
+    fn new(
+        problem: &'p GtspProblem<R>,
+        current: &'p Solution<R>,
+        i: usize,
+        h: usize,
+        reversal_cost: R,
+    ) -> Self {
+        let t = |k: usize| current.tour()[k % current.tour().len()];
+        let score_increase = current.arc_cost(problem, i, i + 1)
+            + current.arc_cost(problem, h, h + 1)
+            - problem.dist(t(i), t(h))
+            - problem.dist(t(i + 1), t(h + 1))
+            - reversal_cost;
+        Self {
+            problem,
+            current,
+            i,
+            h,
+            score_increase,
+        }
+    }
 }
 
 impl<'p, R: Ring> Move<GtspProblem<R>> for TwoOptMove<'p, R> {
     fn score_increase(&self) -> <GtspProblem<R> as Problem>::Score {
-        let removed_cost = self.current.forward_cost(self.problem, self.i..=self.h + 1);
-        let added_cost = self.current.forward_cost(
-            self.problem,
-            iter::once(self.i)
-                .chain((self.i + 1..=self.h).rev())
-                .chain(iter::once(self.h + 1)),
-        );
-        removed_cost - added_cost
+        self.score_increase
     }
 
     fn is_improving(&self) -> bool {
@@ -36,38 +59,205 @@ impl<'p, R: Ring> Move<GtspProblem<R>> for TwoOptMove<'p, R> {
 
         let mut tour = self.current.tour().to_owned();
         tour[j..k].reverse();
-        Solution::new(self.problem, tour).assert_weight(self.current.weight - self.score_increase())
+        Solution::new(self.problem, tour).assert_weight(self.current.weight - self.score_increase)
     }
 }
 
 impl<R: Ring> MoveNeighborhood<GtspProblem<R>> for TwoOptNeighborhood {
     type Move<'c> = TwoOptMove<'c , R> where R: 'c;
 
-    // TODO: generate on the fly
-    type Iter<'c> = <Vec<Self::Move<'c>> as IntoIterator>::IntoIter where R: 'c;
+    type Iter<'c> = TwoOptMoves<'c, R> where R: 'c;
 
     fn moves_iter<'c, 'p: 'c>(
         problem: &'p GtspProblem<R>,
         current: &'c <GtspProblem<R> as Problem>::Solution,
     ) -> Self::Iter<'c> {
-        let mut moves = Vec::new();
-
-        for i in 0..current.tour().len() - 1 {
-            let j = i + 1;
-            for h in j + 1..current.tour().len() {
-                let k = h + 1;
-                if i == 0 && k == current.tour().len() {
-                    continue;
-                }
-                moves.push(TwoOptMove {
-                    problem,
-                    current,
-                    i,
-                    h,
-                })
+        TwoOptMoves::new(problem, current, ScanOrder::sequential())
+    }
+
+    fn random_moves_iter<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Self::Iter<'c> {
+        let order = ScanOrder::random(current.tour().len(), rng);
+        TwoOptMoves::new(problem, current, order)
+    }
+
+    fn random_move<'c, 'p: 'c>(
+        problem: &'p GtspProblem<R>,
+        current: &'c <GtspProblem<R> as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Option<Self::Move<'c>> {
+        let n = current.tour().len();
+        if n < 4 {
+            return None;
+        }
+        let (i, h) = loop {
+            let (i, h) = random_pair(n, rng);
+            if i + 2 <= h && !(i == 0 && h + 1 == n) {
+                break (i, h);
+            }
+        };
+        let reversal_cost = if problem.is_symmetric() {
+            0.into()
+        } else {
+            current.forward_cost(problem, (i + 1..=h).rev())
+                - current.forward_cost(problem, i + 1..=h)
+        };
+        Some(TwoOptMove::new(problem, current, i, h, reversal_cost))
+    }
+}
+
+impl<R: Ring> TabuNeighborhood<GtspProblem<R>> for TwoOptNeighborhood {
+    type Attribute = Edge;
+    type Attributes = [Edge; 2];
+
+    fn removed_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        let t = |k: usize| m.current.tour()[k % m.current.tour().len()];
+        [edge(t(m.i), t(m.i + 1)), edge(t(m.h), t(m.h + 1))]
+    }
+
+    fn added_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        R: 'c,
+    {
+        let t = |k: usize| m.current.tour()[k % m.current.tour().len()];
+        [edge(t(m.i), t(m.h)), edge(t(m.i + 1), t(m.h + 1))]
+    }
+}
+
+impl<R: Ring> CandidateNeighborhood<R> for TwoOptNeighborhood {
+    type Context = ReversalCosts<R>;
+
+    fn context(problem: &GtspProblem<R>, current: &Solution<R>) -> Self::Context {
+        ReversalCosts::new(problem, current)
+    }
+
+    fn candidate_moves<'c>(
+        problem: &'c GtspProblem<R>,
+        current: &'c Solution<R>,
+        reversal_costs: &Self::Context,
+        i: usize,
+        p: usize,
+        moves: &mut Vec<Self::Move<'c>>,
+    ) where
+        R: 'c,
+    {
+        let n = current.tour().len();
+        let (a, b) = (i.min(p), i.max(p));
+        // Either the vertices at `a` and `b` or the vertices after them become neighbors.
+        let before = a.checked_sub(1).map(|a| (a, b - 1));
+        for (i, h) in [Some((a, b)), before].into_iter().flatten() {
+            if i + 2 <= h && !(i == 0 && h + 1 == n) {
+                let reversal_cost = reversal_costs.get(i, h);
+                moves.push(TwoOptMove::new(problem, current, i, h, reversal_cost));
             }
         }
+    }
+}
+
+/// How much the cost of a segment of the tour changes when it is reversed, in constant time.
+///
+/// On asymmetric instances, `forward[k]` and `backward[k]` are the costs of the path from
+/// position 0 to `k` in tour order and in reverse order. On symmetric instances, reversing a
+/// segment does not change the cost of its arcs, and both are empty.
+pub struct ReversalCosts<R> {
+    forward: Vec<R>,
+    backward: Vec<R>,
+}
+
+impl<R: Ring> ReversalCosts<R> {
+    fn new(problem: &GtspProblem<R>, current: &Solution<R>) -> Self {
+        if problem.is_symmetric() {
+            return Self {
+                forward: Vec::new(),
+                backward: Vec::new(),
+            };
+        }
+        let prefix_sums = |cost: &dyn Fn(usize) -> R| {
+            let mut sum = 0.into();
+            let mut sums = vec![sum];
+            for k in 1..current.tour().len() {
+                sum = sum + cost(k - 1);
+                sums.push(sum);
+            }
+            sums
+        };
+        Self {
+            forward: prefix_sums(&|k| current.arc_cost(problem, k, k + 1)),
+            backward: prefix_sums(&|k| current.arc_cost(problem, k + 1, k)),
+        }
+    }
+
+    /// The change for the segment between the positions `i + 1` and `h`.
+    fn get(&self, i: usize, h: usize) -> R {
+        if self.forward.is_empty() {
+            0.into()
+        } else {
+            (self.backward[h] - self.backward[i + 1]) - (self.forward[h] - self.forward[i + 1])
+        }
+    }
+}
+
+pub struct TwoOptMoves<'p, R> {
+    problem: &'p GtspProblem<R>,
+    current: &'p Solution<R>,
+    reversal_costs: ReversalCosts<R>,
+    order: ScanOrder,
+    /// The number of positions `i` whose moves were generated.
+    done: usize,
+    /// The number of moves generated for the current position.
+    t: usize,
+}
+
+impl<'p, R: Ring> TwoOptMoves<'p, R> {
+    fn new(problem: &'p GtspProblem<R>, current: &'p Solution<R>, order: ScanOrder) -> Self {
+        Self {
+            problem,
+            current,
+            reversal_costs: ReversalCosts::new(problem, current),
+            order,
+            done: 0,
+            t: 0,
+        }
+    }
+}
 
-        moves.into_iter()
+impl<'p, R: Ring> Iterator for TwoOptMoves<'p, R> {
+    type Item = TwoOptMove<'p, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.current.tour().len();
+        loop {
+            if self.done >= n {
+                return None;
+            }
+            // The moves of `i` reverse the segments from `i + 1` to every `h` in `i + 2..n`.
+            let i = self.order.position(self.done);
+            let len = n.saturating_sub(i + 2);
+            if self.t >= len {
+                self.done += 1;
+                self.t = 0;
+                continue;
+            }
+
+            let h = i + 2 + self.order.inner(self.t, len);
+            self.t += 1;
+            if i == 0 && h + 1 == n {
+                continue;
+            }
+
+            return Some(TwoOptMove::new(
+                self.problem,
+                self.current,
+                i,
+                h,
+                self.reversal_costs.get(i, h),
+            ));
+        }
     }
 }
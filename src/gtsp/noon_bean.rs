@@ -0,0 +1,164 @@
+use std::{
+    fmt::Display,
+    io::{BufRead, Write},
+    iter, mem,
+};
+
+use anyhow::{bail, Context as _};
+use itertools::Itertools as _;
+
+use crate::{
+    gtsp::{warm_start::read_tsplib_tour, GtspProblem, Solution},
+    Ring,
+};
+
+/// The Noon-Bean transformation of a GTSP instance into an equivalent asymmetric TSP instance on
+/// the same vertices.
+///
+/// The vertices of every cluster are connected by a cycle of zero cost arcs, so that an optimal
+/// ATSP tour enters a cluster at some vertex, visits the whole cluster along the cycle and leaves
+/// it from the predecessor of the entry vertex. Leaving a cluster from vertex `u` to a vertex `w`
+/// of another cluster therefore costs as much as the GTSP arc from the successor of `u` to `w`,
+/// plus a big constant `M` that makes entering a cluster more than once unprofitable. All other
+/// arcs inside of a cluster are forbidden by an even larger cost. The weight of the ATSP tour is
+/// the weight of the GTSP tour plus `m * M`.
+pub struct NoonBean<R> {
+    dist: Vec<Vec<R>>,
+    big_m: R,
+}
+
+impl<R: Ring> NoonBean<R> {
+    pub fn new(problem: &GtspProblem<R>) -> Self {
+        let n = problem.number_of_vertices;
+        let m = problem.clusters.len();
+        let times = |k: usize, x: R| iter::repeat(x).take(k).sum::<R>();
+
+        let max_dist = (0..n)
+            .tuple_combinations()
+            .flat_map(|(u, v)| [problem.dist(u, v), problem.dist(v, u)])
+            .max()
+            .unwrap_or(0.into());
+        let big_m = times(m, max_dist) + 1.into();
+        let forbidden = times(m, big_m + max_dist) + 1.into();
+
+        let mut successor = vec![0; n];
+        for c in problem.clusters.iter() {
+            for (&u, &v) in c.iter().circular_tuple_windows() {
+                successor[u] = v;
+            }
+        }
+
+        let dist = (0..n)
+            .map(|u| {
+                (0..n)
+                    .map(|v| {
+                        if problem.cluster_of(u) != problem.cluster_of(v) {
+                            problem.dist(successor[u], v) + big_m
+                        } else if successor[u] == v && u != v {
+                            0.into()
+                        } else {
+                            forbidden
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self { dist, big_m }
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dist.len()
+    }
+
+    pub fn dist(&self, u: usize, v: usize) -> R {
+        self.dist[u][v]
+    }
+
+    pub fn big_m(&self) -> R {
+        self.big_m
+    }
+
+    /// Maps an ATSP tour to the GTSP tour that visits every cluster at the vertex where the ATSP
+    /// tour first enters it. ATSP tours that enter clusters more than once, which optimal tours
+    /// never do, are still mapped to valid GTSP tours.
+    pub fn to_solution(
+        &self,
+        problem: &GtspProblem<R>,
+        tour: &[usize],
+    ) -> anyhow::Result<Solution<R>> {
+        if let Some(&v) = tour.iter().find(|&&v| v >= self.dimension()) {
+            bail!("vertex {} does not exist", v + 1);
+        }
+        if tour.len() != self.dimension() || !tour.iter().all_unique() {
+            bail!("not a tour of all {} vertices", self.dimension());
+        }
+
+        let first_entry = (0..tour.len())
+            .find(|&i| {
+                problem.cluster_of(tour[i])
+                    != problem.cluster_of(tour[(i + tour.len() - 1) % tour.len()])
+            })
+            .context("all vertices are in the same cluster")?;
+
+        let mut visited = vec![false; problem.clusters.len()];
+        let gtsp_tour = (first_entry..first_entry + tour.len())
+            .map(|i| tour[i % tour.len()])
+            .filter(|&v| {
+                let c = problem.cluster_of(v);
+                !mem::replace(&mut visited[c], true)
+            })
+            .collect_vec();
+
+        problem.validate_tour(&gtsp_tour)?;
+        Ok(Solution::new(problem, gtsp_tour))
+    }
+
+    /// Maps a GTSP solution to the corresponding ATSP tour, for example to pass it as an initial
+    /// tour to an external solver.
+    pub fn to_tour(&self, problem: &GtspProblem<R>, solution: &Solution<R>) -> Vec<usize> {
+        solution
+            .tour()
+            .iter()
+            .flat_map(|&v| {
+                let c = &problem.clusters[problem.cluster_of(v)];
+                let entry = c.iter().position(|&u| u == v).unwrap();
+                c[entry..].iter().chain(&c[..entry]).copied()
+            })
+            .collect()
+    }
+
+    /// Reads an ATSP tour in the TSPLIB `.tour` format and maps it to a GTSP solution.
+    pub fn read_tsplib_tour(
+        &self,
+        problem: &GtspProblem<R>,
+        mut reader: impl BufRead,
+    ) -> anyhow::Result<Solution<R>> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+        self.to_solution(problem, &read_tsplib_tour(&content)?)
+    }
+
+    /// Writes the ATSP instance in the TSPLIB format as an explicit full matrix.
+    pub fn write_tsplib(&self, mut writer: impl Write, name: &str) -> anyhow::Result<()>
+    where
+        R: Display,
+    {
+        writeln!(writer, "NAME: {name}")?;
+        writeln!(writer, "TYPE: ATSP")?;
+        writeln!(
+            writer,
+            "COMMENT: Noon-Bean transformation, M = {}",
+            self.big_m
+        )?;
+        writeln!(writer, "DIMENSION: {}", self.dimension())?;
+        writeln!(writer, "EDGE_WEIGHT_TYPE: EXPLICIT")?;
+        writeln!(writer, "EDGE_WEIGHT_FORMAT: FULL_MATRIX")?;
+        writeln!(writer, "EDGE_WEIGHT_SECTION")?;
+        for row in &self.dist {
+            writeln!(writer, "{}", row.iter().format(" "))?;
+        }
+        writeln!(writer, "EOF")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
+};
+
+use itertools::Itertools as _;
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    multistart::PostProcessing,
+    termination::Termination,
+    ImprovementHeuristic, Ring,
+};
+
+pub const DEFAULT_POOL_SIZE: usize = 10;
+
+/// The fraction of the arcs in which a solution has to differ from every elite solution to
+/// enter the pool, unless it is better than all of them.
+const MIN_DIVERSITY: f64 = 0.1;
+
+/// Local search is applied to the best intermediate solution of each of this many equal parts of
+/// a path.
+const LOCAL_SEARCH_POINTS: usize = 3;
+
+/// Path relinking between the solutions of an elite pool.
+///
+/// A solution enters the pool if it is better than its worst solution (or the pool is not full)
+/// and it differs from every elite solution in enough arcs, or it is better than all of them. It
+/// replaces the most similar worse elite solution. Every pair of elite solutions is relinked once,
+/// starting from the better one: at every step, the next position of the tour adopts the cluster
+/// and vertex of the guiding solution, by swapping the cluster into place. The local search
+/// improves the best intermediate solution of every part of the path, and the result is offered
+/// to the pool, until the termination or no pair is left.
+pub struct PathRelinking<R, L> {
+    termination: Termination,
+    local_search: L,
+    pool_size: usize,
+    elite: Vec<Solution<R>>,
+}
+
+impl<R, L> PathRelinking<R, L> {
+    pub fn new(termination: Termination, local_search: L) -> Self {
+        Self::with_pool_size(termination, local_search, DEFAULT_POOL_SIZE)
+    }
+
+    pub fn with_pool_size(termination: Termination, local_search: L, pool_size: usize) -> Self {
+        Self {
+            termination,
+            local_search,
+            pool_size,
+            elite: Vec::new(),
+        }
+    }
+
+    pub fn elite(&self) -> &[Solution<R>] {
+        &self.elite
+    }
+}
+
+impl<R: Ring, L: ImprovementHeuristic<GtspProblem<R>>> PathRelinking<R, L> {
+    /// The solution found on the path from `initiating` to `guiding`, if they differ in more than
+    /// one step.
+    fn relink(
+        &mut self,
+        instance: &GtspProblem<R>,
+        initiating: &Solution<R>,
+        guiding: &Solution<R>,
+    ) -> Option<Solution<R>> {
+        let mut intermediates = path(instance, initiating, guiding);
+        // The last step reaches the guiding solution.
+        intermediates.pop();
+        if intermediates.is_empty() {
+            return None;
+        }
+
+        let part = intermediates.len().div_ceil(LOCAL_SEARCH_POINTS);
+        intermediates
+            .chunks(part)
+            .map(|c| c.iter().min_by_key(|s| s.weight()).unwrap().clone())
+            .collect_vec()
+            .into_iter()
+            .map(|s| self.local_search.improve(instance, s))
+            .min_by_key(|s| s.weight())
+    }
+}
+
+/// The solutions on the path from `initiating` to `guiding`, ending with `guiding` unless they
+/// are the same tour. The initiating tour is rotated to start with the cluster of the guiding
+/// tour and, on symmetric instances, reversed if that makes more positions agree. At every step,
+/// the next position that differs adopts the cluster and vertex of the guiding tour, by swapping
+/// the cluster into place.
+pub fn path<R: Ring>(
+    instance: &GtspProblem<R>,
+    initiating: &Solution<R>,
+    guiding: &Solution<R>,
+) -> Vec<Solution<R>> {
+    let guiding = guiding.tour();
+    let oriented = |mut tour: Vec<usize>| {
+        let start = tour
+            .iter()
+            .position(|&v| instance.cluster_of(v) == instance.cluster_of(guiding[0]))
+            .unwrap();
+        tour.rotate_left(start);
+        tour
+    };
+    let agreements = |tour: &[usize]| tour.iter().zip(guiding).filter(|(u, v)| u == v).count();
+    let mut tour = oriented(initiating.tour().to_owned());
+    if instance.is_symmetric() {
+        let reversed = oriented(initiating.tour().iter().rev().copied().collect());
+        if agreements(&reversed) > agreements(&tour) {
+            tour = reversed;
+        }
+    }
+    let mut position = vec![0; instance.number_of_clusters()];
+    for (k, &v) in tour.iter().enumerate() {
+        position[instance.cluster_of(v)] = k;
+    }
+
+    let mut solutions = Vec::new();
+    for (k, &v) in guiding.iter().enumerate() {
+        if tour[k] == v {
+            continue;
+        }
+        let j = position[instance.cluster_of(v)];
+        tour.swap(k, j);
+        position[instance.cluster_of(tour[j])] = j;
+        tour[k] = v;
+        position[instance.cluster_of(v)] = k;
+        solutions.push(Solution::new(instance, tour.clone()));
+    }
+    solutions
+}
+
+/// The number of arcs of `a` that are not in `b`.
+fn distance<R: Ring>(instance: &GtspProblem<R>, a: &Solution<R>, b: &Solution<R>) -> usize {
+    let arcs = |s: &Solution<R>| {
+        let tour = s.tour();
+        (0..tour.len())
+            .map(|k| {
+                let (u, v) = (tour[k], tour[(k + 1) % tour.len()]);
+                if instance.is_symmetric() {
+                    (u.min(v), u.max(v))
+                } else {
+                    (u, v)
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let b_arcs = arcs(b).into_iter().collect::<HashSet<_>>();
+    arcs(a)
+        .into_iter()
+        .filter(|arc| !b_arcs.contains(arc))
+        .count()
+}
+
+fn hash<R: Hash>(solution: &Solution<R>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    solution.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<R, L> PostProcessing<GtspProblem<R>> for PathRelinking<R, L>
+where
+    R: Ring + Hash,
+    L: ImprovementHeuristic<GtspProblem<R>>,
+{
+    fn add_solution(&mut self, instance: &GtspProblem<R>, solution: &Solution<R>) {
+        let distances = self
+            .elite
+            .iter()
+            .map(|e| distance(instance, solution, e))
+            .collect_vec();
+        if distances.contains(&0) {
+            return;
+        }
+
+        let min_distance = (MIN_DIVERSITY * solution.tour().len() as f64).ceil() as usize;
+        let is_best = self.elite.iter().all(|e| solution.weight() < e.weight());
+        if !is_best && distances.iter().any(|&d| d < min_distance) {
+            return;
+        }
+        if self.elite.len() < self.pool_size {
+            self.elite.push(solution.clone());
+        } else if let Some(i) = (0..self.elite.len())
+            .filter(|&i| self.elite[i].weight() > solution.weight())
+            .min_by_key(|&i| distances[i])
+        {
+            self.elite[i] = solution.clone();
+        }
+    }
+
+    fn process(&mut self, instance: &GtspProblem<R>, best: Solution<R>) -> Solution<R> {
+        self.add_solution(instance, &best);
+
+        let mut relinked = HashSet::new();
+        while !self.termination.should_terminate() {
+            let Some((initiating, guiding)) = self
+                .elite
+                .iter()
+                .tuple_combinations()
+                .map(|(a, b)| {
+                    if a.weight() <= b.weight() {
+                        (a, b)
+                    } else {
+                        (b, a)
+                    }
+                })
+                .find(|&(a, b)| !relinked.contains(&(hash(a), hash(b))))
+                .map(|(a, b)| (a.clone(), b.clone()))
+            else {
+                break;
+            };
+            relinked.insert((hash(&initiating), hash(&guiding)));
+
+            if let Some(solution) = self.relink(instance, &initiating, &guiding) {
+                self.add_solution(instance, &solution);
+            }
+            self.termination.iteration();
+        }
+
+        self.elite
+            .iter()
+            .chain([&best])
+            .min_by_key(|s| s.weight())
+            .unwrap()
+            .clone()
+    }
+}
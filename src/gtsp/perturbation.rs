@@ -0,0 +1,35 @@
+use rand::{seq::index::sample, Rng};
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    Perturbation, Ring,
+};
+
+/// Cuts the tour into four segments `A B C D` at random positions and reconnects them as
+/// `A C B D`, keeping the selected vertices. Tours of fewer than 4 clusters are returned
+/// unchanged.
+pub struct DoubleBridge<G: Rng> {
+    rng: G,
+}
+
+impl<G: Rng> DoubleBridge<G> {
+    pub fn new(rng: G) -> Self {
+        Self { rng }
+    }
+}
+
+impl<G: Rng, R: Ring> Perturbation<GtspProblem<R>> for DoubleBridge<G> {
+    fn perturb(&mut self, instance: &GtspProblem<R>, current: &Solution<R>) -> Solution<R> {
+        let tour = current.tour();
+        let n = tour.len();
+        if n < 4 {
+            return current.clone();
+        }
+
+        let mut cuts = sample(&mut self.rng, n - 1, 3).into_vec();
+        cuts.sort_unstable();
+        let [a, b, c] = [cuts[0] + 1, cuts[1] + 1, cuts[2] + 1];
+        let tour = [&tour[..a], &tour[b..c], &tour[a..b], &tour[c..]].concat();
+        Solution::new(instance, tour)
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    io::{BufRead, Cursor},
+    str::FromStr,
+};
+
+use anyhow::{bail, Context};
+
+use crate::{
+    gtsp::{GtspProblem, Solution},
+    InitialSolution, Ring,
+};
+
+/// Starts from a fixed, previously computed tour, for example to continue optimizing the best
+/// known solution or to polish the result of another solver.
+#[derive(Debug, Clone)]
+pub struct WarmStart<R> {
+    solution: Solution<R>,
+}
+
+impl<R: Ring> WarmStart<R> {
+    /// Validates the tour against the instance and recomputes its weight.
+    pub fn new(problem: &GtspProblem<R>, tour: Vec<usize>) -> anyhow::Result<Self> {
+        problem.validate_tour(&tour)?;
+        Ok(Self {
+            solution: Solution::new(problem, tour),
+        })
+    }
+
+    /// Reads a tour either in the text format of the GTSP instances library (see
+    /// `Solution::read_from_text`) or in the TSPLIB `.tour` format. The weight stated in the
+    /// file, if any, is ignored.
+    pub fn read_from_text(
+        problem: &GtspProblem<R>,
+        mut reader: impl BufRead,
+    ) -> anyhow::Result<Self>
+    where
+        R: FromStr,
+        <R as FromStr>::Err: std::error::Error + Send + Sync + 'static,
+    {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+
+        let first_line = content.lines().next().context("read line")?;
+        let tour = if first_line.trim().parse::<usize>().is_ok() {
+            Solution::<R>::read_from_text(Cursor::new(&content))?.tour
+        } else {
+            read_tsplib_tour(&content)?
+        };
+
+        Self::new(problem, tour).context("invalid tour")
+    }
+
+    pub fn solution(&self) -> &Solution<R> {
+        &self.solution
+    }
+}
+
+impl<R: Ring> InitialSolution<GtspProblem<R>> for WarmStart<R> {
+    fn make_intial_solution(&mut self, _instance: &GtspProblem<R>) -> Solution<R> {
+        self.solution.clone()
+    }
+}
+
+pub(crate) fn read_tsplib_tour(content: &str) -> anyhow::Result<Vec<usize>> {
+    let mut lines = content.lines().map(str::trim);
+    lines
+        .by_ref()
+        .find(|&line| line == "TOUR_SECTION")
+        .context("no TOUR_SECTION found")?;
+
+    let mut tour = Vec::new();
+    for num in lines.flat_map(str::split_whitespace) {
+        match num.parse::<isize>()? {
+            -1 => return Ok(tour),
+            v if v >= 1 => tour.push(v as usize - 1),
+            _ => bail!("invalid vertex {num}"),
+        }
+    }
+    bail!("unterminated TOUR_SECTION")
+}
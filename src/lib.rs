@@ -1,10 +1,13 @@
 use itertools::Itertools;
+use rand::{seq::SliceRandom as _, Rng};
 use std::{
     fmt::Debug,
+    hash::Hash,
     iter::Sum,
     ops::{Add, Neg, Sub},
 };
 
+pub mod acceptance;
 pub mod chain;
 pub mod cycle_neighborhoods;
 pub mod gtsp;
@@ -30,6 +33,37 @@ impl<
 {
 }
 
+/// Scores that can be approximated by floating point numbers, for heuristics that accept worse
+/// solutions with a probability or up to a threshold depending on the score difference.
+pub trait ToF64 {
+    fn to_f64(self) -> f64;
+}
+
+/// Scores that can be obtained by rounding floating point numbers, for heuristics that scale
+/// scores by a factor.
+pub trait FromF64 {
+    fn from_f64(x: f64) -> Self;
+}
+
+macro_rules! impl_f64_conversions {
+    ($($t: ty),*) => {
+        $(
+            impl ToF64 for $t {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+
+            impl FromF64 for $t {
+                fn from_f64(x: f64) -> Self {
+                    x.round() as $t
+                }
+            }
+        )*
+    };
+}
+impl_f64_conversions!(i8, i16, i32, i64, i128, isize);
+
 pub trait Problem {
     type Score: Ord;
     type Solution;
@@ -59,6 +93,39 @@ pub trait MoveNeighborhood<P: Problem> {
         P: 'c;
 
     fn moves_iter<'c, 'p: 'c>(problem: &'p P, current: &'c P::Solution) -> Self::Iter<'c>;
+
+    /// The moves of `moves_iter` in a random order, generated lazily where possible, for
+    /// randomized scans that stop early.
+    fn random_moves_iter<'c, 'p: 'c>(
+        problem: &'p P,
+        current: &'c P::Solution,
+        rng: &mut impl Rng,
+    ) -> Self::Iter<'c>;
+
+    /// A random move of `moves_iter`, or `None` if there is none. Neighborhoods that can draw a
+    /// move in constant time override the default, which takes the first move of a random scan.
+    fn random_move<'c, 'p: 'c>(
+        problem: &'p P,
+        current: &'c P::Solution,
+        rng: &mut impl Rng,
+    ) -> Option<Self::Move<'c>> {
+        Self::random_moves_iter(problem, current, rng).next()
+    }
+}
+
+/// A neighborhood whose moves can be described by attributes of the solutions, such as the edges
+/// they remove and add, for use in attribute-based tabu lists.
+pub trait TabuNeighborhood<P: Problem>: MoveNeighborhood<P> {
+    type Attribute: Copy + Eq + Hash;
+    type Attributes: IntoIterator<Item = Self::Attribute>;
+
+    fn removed_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        P: 'c;
+
+    fn added_attributes<'c>(m: &Self::Move<'c>) -> Self::Attributes
+    where
+        P: 'c;
 }
 
 impl<P: Problem, N: MoveNeighborhood<P>> Neighborhood<P> for N {
@@ -117,7 +184,7 @@ where
         problem: &'p P,
         current: &'c <P as Problem>::Solution,
     ) -> Self::Iter<'c> {
-        let old_score = P::score(&current);
+        let old_score = P::score(current);
         N::neighbors_iter(problem, current)
             .map(|solution| TrivialMove {
                 old_score,
@@ -126,16 +193,37 @@ where
             .collect::<Vec<_>>()
             .into_iter()
     }
+
+    fn random_moves_iter<'c, 'p: 'c>(
+        problem: &'p P,
+        current: &'c <P as Problem>::Solution,
+        rng: &mut impl Rng,
+    ) -> Self::Iter<'c> {
+        let mut moves = Self::moves_iter(problem, current).collect_vec();
+        moves.shuffle(rng);
+        moves.into_iter()
+    }
 }
 
 pub trait InitialSolution<P: Problem> {
     fn make_intial_solution(&mut self, instance: &P) -> P::Solution;
 }
 
+impl<P: Problem, I: InitialSolution<P> + ?Sized> InitialSolution<P> for Box<I> {
+    fn make_intial_solution(&mut self, instance: &P) -> P::Solution {
+        (**self).make_intial_solution(instance)
+    }
+}
+
 pub trait ImprovementHeuristic<P: Problem> {
     fn improve(&mut self, instance: &P, current: P::Solution) -> P::Solution;
 }
 
+/// A random change of a solution, used to leave a local optimum.
+pub trait Perturbation<P: Problem> {
+    fn perturb(&mut self, instance: &P, current: &P::Solution) -> P::Solution;
+}
+
 pub trait MetaHeuristic<P: Problem> {
     fn run(self, instance: &P) -> P::Solution;
 }
@@ -161,6 +249,18 @@ where
     Im: ImprovementHeuristic<P>,
 {
     fn run(mut self, instance: &P) -> <P as Problem>::Solution {
+        self.make_intial_solution(instance)
+    }
+}
+
+/// An improved initial solution can itself serve as the initial solution of another heuristic.
+impl<P, In, Im> InitialSolution<P> for ImproveInitial<In, Im>
+where
+    P: Problem,
+    In: InitialSolution<P>,
+    Im: ImprovementHeuristic<P>,
+{
+    fn make_intial_solution(&mut self, instance: &P) -> <P as Problem>::Solution {
         self.improvement
             .improve(instance, self.initial.make_intial_solution(instance))
     }
@@ -1,35 +1,64 @@
 use std::marker::PhantomData;
 
+use rand::{rngs::SmallRng, Rng};
+
 use crate::{termination::Termination, ImprovementHeuristic, Move, MoveNeighborhood, Problem};
 
-pub struct LocalSearch<N> {
+/// Which improving move the local search applies in every iteration.
+pub enum Pivoting<G = SmallRng> {
+    /// Scan the whole neighborhood and apply the best move.
+    BestImprovement,
+    /// Apply the first improving move in the order of the neighborhood.
+    FirstImprovement,
+    /// Apply the first improving move, scanning the neighborhood in a random order (see
+    /// `MoveNeighborhood::random_moves_iter`).
+    RandomizedFirstImprovement(G),
+}
+
+pub struct LocalSearch<N, G = SmallRng> {
     termination: Termination,
+    pivoting: Pivoting<G>,
     _n: PhantomData<N>,
 }
 
 impl<N> LocalSearch<N> {
     pub fn new(termination: Termination) -> Self {
+        Self::with_pivoting(termination, Pivoting::BestImprovement)
+    }
+}
+
+impl<N, G: Rng> LocalSearch<N, G> {
+    pub fn with_pivoting(termination: Termination, pivoting: Pivoting<G>) -> Self {
         Self {
             termination,
+            pivoting,
             _n: PhantomData,
         }
     }
 }
 
-impl<P, N> ImprovementHeuristic<P> for LocalSearch<N>
+impl<P, N, G> ImprovementHeuristic<P> for LocalSearch<N, G>
 where
     P: Problem,
     N: MoveNeighborhood<P>,
+    G: Rng,
 {
     fn improve(&mut self, instance: &P, current: P::Solution) -> P::Solution {
         let mut best = current;
 
         while !self.termination.should_terminate() {
-            let Some(new_best) = N::moves_iter(instance, &best)
-                .max_by_key(|m| m.score_increase())
-                .filter(|m| m.is_improving())
-                .map(|m| m.into_solution())
-            else {
+            let improving_move = match &mut self.pivoting {
+                Pivoting::BestImprovement => N::moves_iter(instance, &best)
+                    .max_by_key(|m| m.score_increase())
+                    .filter(|m| m.is_improving()),
+                Pivoting::FirstImprovement => {
+                    N::moves_iter(instance, &best).find(|m| m.is_improving())
+                }
+                Pivoting::RandomizedFirstImprovement(rng) => {
+                    N::random_moves_iter(instance, &best, rng).find(|m| m.is_improving())
+                }
+            };
+            let Some(new_best) = improving_move.map(|m| m.into_solution()) else {
                 break;
             };
             best = new_best;
@@ -2,38 +2,68 @@ use std::iter;
 
 use crate::{termination::Termination, MetaHeuristic, Problem};
 
-pub struct Multistart<F> {
+/// A phase after the starts of `Multistart`, which sees the solution of every start.
+pub trait PostProcessing<P: Problem> {
+    fn add_solution(&mut self, instance: &P, solution: &P::Solution);
+
+    /// Called once after the starts terminated, with the best solution they found.
+    fn process(&mut self, instance: &P, best: P::Solution) -> P::Solution;
+}
+
+/// Returns the best solution of the starts.
+pub struct NoPostProcessing;
+
+impl<P: Problem> PostProcessing<P> for NoPostProcessing {
+    fn add_solution(&mut self, _instance: &P, _solution: &P::Solution) {}
+
+    fn process(&mut self, _instance: &P, best: P::Solution) -> P::Solution {
+        best
+    }
+}
+
+pub struct Multistart<F, Q = NoPostProcessing> {
     termination: Termination,
     factory: F,
+    post_processing: Q,
 }
 
 impl<F> Multistart<F> {
     pub fn new(termination: Termination, factory: F) -> Self {
+        Self::with_post_processing(termination, factory, NoPostProcessing)
+    }
+}
+
+impl<F, Q> Multistart<F, Q> {
+    pub fn with_post_processing(termination: Termination, factory: F, post_processing: Q) -> Self {
         Self {
             termination,
             factory,
+            post_processing,
         }
     }
 }
 
-impl<P, M, F> MetaHeuristic<P> for Multistart<F>
+impl<P, M, F, Q> MetaHeuristic<P> for Multistart<F, Q>
 where
     P: Problem,
     M: MetaHeuristic<P>,
     F: FnMut() -> M,
+    Q: PostProcessing<P>,
 {
     fn run(mut self, instance: &P) -> P::Solution {
         let mut solutions = iter::repeat_with(|| (self.factory)().run(instance));
 
         let mut best = solutions.next().unwrap();
+        self.post_processing.add_solution(instance, &best);
 
         while !self.termination.should_terminate() {
             let next = solutions.next().unwrap();
+            self.post_processing.add_solution(instance, &next);
             if P::score(&next) > P::score(&best) {
                 best = next;
             }
         }
 
-        best
+        self.post_processing.process(instance, best)
     }
 }
@@ -1,47 +1,174 @@
-use std::{collections::VecDeque, marker::PhantomData};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
 
-use crate::{termination::Termination, ImprovementHeuristic, Neighborhood, Problem};
+use rand::{rngs::SmallRng, Rng as _};
 
-pub struct TabuSearch<N, const L: usize> {
+use crate::{
+    termination::Termination, ImprovementHeuristic, Move, Problem, Ring, TabuNeighborhood,
+};
+
+enum TenureKind {
+    Fixed(usize),
+    Randomized {
+        min: usize,
+        max: usize,
+        rng: SmallRng,
+    },
+    Reactive {
+        min: usize,
+        max: usize,
+        tenure: usize,
+        last_change: usize,
+        visited: HashMap<u64, usize>,
+    },
+}
+
+/// For how many iterations removed attributes stay tabu.
+pub struct Tenure {
+    kind: TenureKind,
+}
+
+impl Tenure {
+    pub fn fixed(tenure: usize) -> Self {
+        Self {
+            kind: TenureKind::Fixed(tenure),
+        }
+    }
+
+    /// A tenure drawn uniformly from `min..=max` for every attribute.
+    pub fn randomized(min: usize, max: usize, rng: SmallRng) -> Self {
+        Self {
+            kind: TenureKind::Randomized { min, max, rng },
+        }
+    }
+
+    /// Reactive tabu search: the tenure grows by 10% whenever a solution is revisited and shrinks
+    /// by 10% after twice as many iterations as the tenure without revisits, staying within
+    /// `min..=max`.
+    pub fn reactive(min: usize, max: usize) -> Self {
+        Self {
+            kind: TenureKind::Reactive {
+                min,
+                max,
+                tenure: min,
+                last_change: 0,
+                visited: HashMap::new(),
+            },
+        }
+    }
+
+    fn reset(&mut self) {
+        if let TenureKind::Reactive {
+            min,
+            tenure,
+            last_change,
+            visited,
+            ..
+        } = &mut self.kind
+        {
+            *tenure = *min;
+            *last_change = 0;
+            visited.clear();
+        }
+    }
+
+    fn next(&mut self) -> usize {
+        match &mut self.kind {
+            TenureKind::Fixed(tenure) => *tenure,
+            TenureKind::Randomized { min, max, rng } => rng.gen_range(*min..=*max),
+            TenureKind::Reactive { tenure, .. } => *tenure,
+        }
+    }
+
+    fn visit(&mut self, iteration: usize, solution: &impl Hash) {
+        let TenureKind::Reactive {
+            min,
+            max,
+            tenure,
+            last_change,
+            visited,
+        } = &mut self.kind
+        else {
+            return;
+        };
+
+        let mut hasher = DefaultHasher::new();
+        solution.hash(&mut hasher);
+        if visited.insert(hasher.finish(), iteration).is_some() {
+            *tenure = (*tenure + *tenure / 10 + 1).min(*max);
+            *last_change = iteration;
+        } else if iteration - *last_change > 2 * *tenure {
+            *tenure = tenure.saturating_sub(*tenure / 10 + 1).max(*min);
+            *last_change = iteration;
+        }
+    }
+}
+
+/// Tabu search that forbids moves adding back attributes that were removed recently. Tabu moves
+/// are still allowed if they lead to a new best solution (aspiration).
+pub struct TabuSearch<N> {
     termination: Termination,
+    tenure: Tenure,
     _n: PhantomData<N>,
 }
 
-impl<N, const L: usize> TabuSearch<N, L> {
-    pub fn new(termination: Termination) -> Self {
+impl<N> TabuSearch<N> {
+    pub fn new(termination: Termination, tenure: Tenure) -> Self {
         Self {
             termination,
+            tenure,
             _n: PhantomData,
         }
     }
 }
 
-impl<P, N, const L: usize> ImprovementHeuristic<P> for TabuSearch<N, L>
+impl<P, N> ImprovementHeuristic<P> for TabuSearch<N>
 where
     P: Problem,
-    P::Solution: Clone + PartialEq,
-    N: Neighborhood<P>,
+    P::Solution: Clone + Hash,
+    P::Score: Ring,
+    N: TabuNeighborhood<P>,
 {
     fn improve(&mut self, instance: &P, current: P::Solution) -> P::Solution {
-        let mut best = current;
-        let mut tabu_list = VecDeque::with_capacity(L + 1);
-        tabu_list.push_back(best.clone());
+        let mut best = current.clone();
+        let mut current = current;
+        // For every tabu attribute, the first iteration in which it is allowed again.
+        let mut tabu_until = HashMap::new();
+        let mut iteration = 0;
+        self.tenure.reset();
+
         while !self.termination.should_terminate() {
-            let Some(best_neighbor) = N::neighbors_iter(instance, &tabu_list.back().unwrap())
-                .filter(|s| !tabu_list.contains(s))
-                .max_by_key(|s| P::score(s))
+            let current_score = P::score(&current);
+            let best_score = P::score(&best);
+            let is_tabu = |m: &N::Move<'_>| {
+                N::added_attributes(m)
+                    .into_iter()
+                    .any(|a| tabu_until.get(&a).is_some_and(|&t| t > iteration))
+            };
+
+            let Some((_, best_move)) = N::moves_iter(instance, &current)
+                .map(|m| (m.score_increase(), m))
+                .filter(|(increase, m)| current_score + *increase > best_score || !is_tabu(m))
+                .max_by_key(|(increase, _)| *increase)
             else {
                 break;
             };
 
-            if P::score(&best_neighbor) > P::score(&best) {
-                best = best_neighbor.clone();
+            for a in N::removed_attributes(&best_move) {
+                tabu_until.insert(a, iteration + self.tenure.next());
             }
+            current = best_move.into_solution();
+            self.tenure.visit(iteration, &current);
 
-            tabu_list.push_back(best_neighbor);
-            if tabu_list.len() > L {
-                tabu_list.pop_front();
+            if P::score(&current) > P::score(&best) {
+                best = current.clone();
             }
+
+            iteration += 1;
+            tabu_until.retain(|_, &mut t| t > iteration);
             self.termination.iteration();
         }
 
@@ -0,0 +1,109 @@
+mod common;
+
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+use gtsp::{
+    acceptance::{
+        AcceptanceCriterion, AcceptanceSearch, GreatDeluge, LateAcceptance, ThresholdAccepting,
+    },
+    gtsp::{neighborhoods::SwapNeighborhood, GtspProblem, RandomSolution},
+    termination::Termination,
+    ImprovementHeuristic as _, InitialSolution as _, Move as _, MoveNeighborhood as _,
+};
+use rand::{rngs::SmallRng, SeedableRng as _};
+
+type P = GtspProblem<i64>;
+
+/// Starts the criterion at `start` and returns which of the `(current, candidate)` score pairs it
+/// accepts, in order.
+fn accepted(
+    mut criterion: impl AcceptanceCriterion<P>,
+    start: i64,
+    moves: &[(i64, i64)],
+) -> Vec<bool> {
+    criterion.start(start);
+    moves
+        .iter()
+        .map(|&(current, candidate)| criterion.accepts(current, candidate))
+        .collect()
+}
+
+#[test]
+fn late_acceptance_compares_with_the_history() {
+    assert_eq!(
+        accepted(
+            LateAcceptance::new(2),
+            -100,
+            // The history starts as [-100, -100] and is [-100, -120] after the second move.
+            &[
+                (-120, -100),
+                (-120, -130),
+                (-100, -105),
+                (-100, -120),
+                (-120, -121),
+            ],
+        ),
+        [true, false, false, true, false]
+    );
+}
+
+#[test]
+fn threshold_decays() {
+    assert_eq!(
+        accepted(
+            ThresholdAccepting::new(0.1, 0.5),
+            -100,
+            // The thresholds are 10, 5, 2.5, 1.25.
+            &[(-100, -110), (-100, -106), (-100, -102), (-100, -102)],
+        ),
+        [true, false, true, false]
+    );
+}
+
+#[test]
+fn water_level_rises() {
+    assert_eq!(
+        accepted(
+            GreatDeluge::new(0.1),
+            -100,
+            // The levels are -100, -90, -81.
+            &[(-80, -100), (-80, -95), (-99, -98)],
+        ),
+        [true, false, true]
+    );
+}
+
+/// Rejects every move, recording the score of every candidate.
+struct RejectAll(Rc<RefCell<Vec<i64>>>);
+
+impl AcceptanceCriterion<P> for RejectAll {
+    fn start(&mut self, _score: i64) {}
+
+    fn accepts(&mut self, _current: i64, candidate: i64) -> bool {
+        self.0.borrow_mut().push(candidate);
+        false
+    }
+}
+
+#[test]
+fn search_draws_random_moves() {
+    let problem = common::random_instance(100, 40, true, 0);
+    let current = RandomSolution::new(SmallRng::seed_from_u64(0)).make_intial_solution(&problem);
+    let candidates = Rc::new(RefCell::new(Vec::new()));
+    AcceptanceSearch::<SwapNeighborhood, _, _>::new(
+        Termination::after_iterations(50),
+        RejectAll(candidates.clone()),
+        SmallRng::seed_from_u64(0),
+    )
+    .improve(&problem, current.clone());
+
+    // Every iteration tries a move of the initial solution. The first moves of scans from random
+    // positions would only swap neighboring positions.
+    let scores = SwapNeighborhood::moves_iter(&problem, &current)
+        .map(|m| current.weight() - m.score_increase())
+        .collect::<HashSet<_>>();
+    let candidates = candidates.borrow();
+    assert_eq!(candidates.len(), 50);
+    assert!(candidates.iter().all(|score| scores.contains(&-score)));
+    assert!(candidates.iter().collect::<HashSet<_>>().len() > 40);
+}
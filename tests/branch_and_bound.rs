@@ -0,0 +1,40 @@
+mod common;
+
+use gtsp::{
+    gtsp::{BranchAndBound, ClusterOptimization, DynamicProgramming, NearestNeighbor},
+    termination::Termination,
+    ImproveInitial, MetaHeuristic as _,
+};
+
+fn upper_bound() -> ImproveInitial<NearestNeighbor, ClusterOptimization> {
+    ImproveInitial::new(NearestNeighbor, ClusterOptimization)
+}
+
+#[test]
+fn proves_optimality() {
+    for seed in 0..5 {
+        for symmetric in [true, false] {
+            let problem = common::random_instance(24, 8, symmetric, seed);
+            let optimum = DynamicProgramming::new().run(&problem).weight();
+            let result = BranchAndBound::new(Termination::never(), upper_bound()).solve(&problem);
+            common::assert_valid(&problem, result.solution());
+            assert!(result.is_optimal());
+            assert_eq!(result.solution().weight(), optimum);
+        }
+    }
+}
+
+#[test]
+fn reports_valid_gap_on_early_termination() {
+    for seed in 0..5 {
+        for symmetric in [true, false] {
+            let problem = common::random_instance(24, 8, symmetric, seed);
+            let optimum = DynamicProgramming::new().run(&problem).weight();
+            let result = BranchAndBound::new(Termination::after_iterations(20), upper_bound())
+                .solve(&problem);
+            common::assert_valid(&problem, result.solution());
+            assert!(result.lower_bound() <= optimum);
+            assert!(optimum <= result.solution().weight());
+        }
+    }
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use gtsp::{
+    gtsp::{
+        cluster_optimization::optimize_window, ClusterOptimization, GtspProblem, RandomSolution,
+        Solution, WindowClusterOptimization,
+    },
+    ImprovementHeuristic, InitialSolution as _,
+};
+use itertools::Itertools as _;
+use rand::{rngs::SmallRng, SeedableRng as _};
+
+/// The weight of the best vertex selection at `positions`, keeping the other vertices of the tour.
+fn brute_force_selection(
+    problem: &GtspProblem<i64>,
+    tour: &[usize],
+    positions: impl IntoIterator<Item = usize> + Clone,
+) -> i64 {
+    positions
+        .clone()
+        .into_iter()
+        .map(|k| {
+            problem.clusters()[problem.cluster_of(tour[k])]
+                .iter()
+                .copied()
+        })
+        .multi_cartesian_product()
+        .map(|selection| {
+            let mut tour = tour.to_owned();
+            for (k, v) in positions.clone().into_iter().zip(selection) {
+                tour[k] = v;
+            }
+            Solution::new(problem, tour).weight()
+        })
+        .min()
+        .unwrap()
+}
+
+/// The clusters of the tour, rotated to start with cluster 0.
+fn cluster_order(problem: &GtspProblem<i64>, tour: &[usize]) -> Vec<usize> {
+    let clusters = tour.iter().map(|&v| problem.cluster_of(v)).collect_vec();
+    let start = clusters.iter().position(|&c| c == 0).unwrap();
+    clusters[start..]
+        .iter()
+        .chain(&clusters[..start])
+        .copied()
+        .collect()
+}
+
+fn instances() -> impl Iterator<Item = GtspProblem<i64>> {
+    (0..4).flat_map(|seed| {
+        [true, false].into_iter().flat_map(move |symmetric| {
+            [(9, 5), (16, 6), (20, 7)].map(|(n, m)| common::random_instance(n, m, symmetric, seed))
+        })
+    })
+}
+
+#[test]
+fn cluster_optimization_is_optimal_for_the_cluster_order() {
+    for (seed, problem) in instances().enumerate() {
+        let mut initial = RandomSolution::new(SmallRng::seed_from_u64(seed as u64));
+        for _ in 0..5 {
+            let current = initial.make_intial_solution(&problem);
+            let optimized = ClusterOptimization.improve(&problem, current.clone());
+
+            common::assert_valid(&problem, &optimized);
+            assert_eq!(
+                cluster_order(&problem, optimized.tour()),
+                cluster_order(&problem, current.tour())
+            );
+            assert_eq!(
+                optimized.weight(),
+                brute_force_selection(&problem, current.tour(), 0..current.tour().len())
+            );
+        }
+    }
+}
+
+#[test]
+fn cluster_optimization_never_worsens_the_tour() {
+    for (seed, problem) in instances().enumerate() {
+        let mut initial = RandomSolution::new(SmallRng::seed_from_u64(seed as u64));
+        for _ in 0..5 {
+            let current = initial.make_intial_solution(&problem);
+            let optimized = ClusterOptimization.improve(&problem, current.clone());
+            assert!(optimized.weight() <= current.weight());
+            // An optimal selection is returned unchanged.
+            assert_eq!(
+                ClusterOptimization.improve(&problem, optimized.clone()),
+                optimized
+            );
+
+            let windowed = WindowClusterOptimization::new(3).improve(&problem, current.clone());
+            common::assert_valid(&problem, &windowed);
+            assert!(windowed.weight() <= current.weight());
+            assert!(windowed.weight() >= optimized.weight());
+        }
+    }
+}
+
+#[test]
+fn window_is_optimal_for_the_fixed_ends() {
+    for (seed, problem) in instances().enumerate() {
+        let current = RandomSolution::new(SmallRng::seed_from_u64(seed as u64))
+            .make_intial_solution(&problem);
+        let n = current.tour().len();
+        for (start, window) in (0..n).cartesian_product(1..=3) {
+            let mut tour = current.tour().to_owned();
+            let improved = optimize_window(&problem, &mut tour, start, window);
+            let optimized = Solution::new(&problem, tour);
+
+            let best = brute_force_selection(
+                &problem,
+                current.tour(),
+                (start..start + window).map(|k| k % n),
+            );
+            assert_eq!(optimized.weight(), best);
+            assert_eq!(improved, best < current.weight());
+        }
+    }
+}
@@ -0,0 +1,73 @@
+#![allow(dead_code)]
+
+use std::{fmt::Write as _, io::Cursor};
+
+use gtsp::gtsp::{GtspProblem, Solution};
+use itertools::Itertools as _;
+use rand::{rngs::SmallRng, seq::SliceRandom as _, Rng as _, SeedableRng as _};
+
+/// Random instance with points in the plane, rounded Euclidean distances and, for asymmetric
+/// instances, some random noise on every arc.
+pub fn random_instance(n: usize, m: usize, symmetric: bool, seed: u64) -> GtspProblem<i64> {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let points = (0..n)
+        .map(|_| (rng.gen_range(0.0..1000.0), rng.gen_range(0.0..1000.0)))
+        .collect_vec();
+    let mut vertices = (0..n).collect_vec();
+    vertices.shuffle(&mut rng);
+
+    let mut text = String::new();
+    writeln!(
+        text,
+        "N: {n}\nM: {m}\nSymmetric: {symmetric}\nTriangle: {symmetric}"
+    )
+    .unwrap();
+    for c in 0..m {
+        let cluster = vertices.iter().skip(c).step_by(m).collect_vec();
+        writeln!(
+            text,
+            "{} {}",
+            cluster.len(),
+            cluster.iter().map(|v| *v + 1).join(" ")
+        )
+        .unwrap();
+    }
+    for (i, &(x1, y1)) in points.iter().enumerate() {
+        let row = points.iter().enumerate().map(|(j, &(x2, y2))| {
+            let noise = if symmetric || i == j {
+                0
+            } else {
+                rng.gen_range(0..100)
+            };
+            (f64::hypot(x1 - x2, y1 - y2) as i64) + noise
+        });
+        writeln!(text, "{}", row.format(" ")).unwrap();
+    }
+
+    GtspProblem::read_from_text(Cursor::new(text)).unwrap()
+}
+
+/// Tries every cluster order and every vertex selection.
+pub fn brute_force(problem: &GtspProblem<i64>) -> i64 {
+    let m = problem.number_of_clusters();
+    (1..m)
+        .permutations(m - 1)
+        .flat_map(|order| {
+            std::iter::once(0)
+                .chain(order)
+                .map(|c| problem.clusters()[c].iter().copied())
+                .multi_cartesian_product()
+        })
+        .map(|tour| Solution::new(problem, tour).weight())
+        .min()
+        .unwrap()
+}
+
+/// Checks that the solution is a valid tour with the correct weight.
+pub fn assert_valid(problem: &GtspProblem<i64>, solution: &Solution<i64>) {
+    problem.validate_tour(solution.tour()).unwrap();
+    assert_eq!(
+        Solution::new(problem, solution.tour().to_owned()).weight(),
+        solution.weight()
+    );
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use gtsp::{
+    cycle_neighborhoods::{Cycle, ExploreOnce, OnExhaustion},
+    gtsp::{
+        neighborhoods::{InsertsNeighborhood, SwapNeighborhood, TwoOptNeighborhood},
+        DoubleBridge, GtspProblem, RandomSolution,
+    },
+    termination::Termination,
+    ImprovementHeuristic, InitialSolution as _, Perturbation as _,
+};
+use itertools::Itertools as _;
+use rand::{rngs::SmallRng, SeedableRng as _};
+
+fn heuristics() -> [Box<dyn ImprovementHeuristic<GtspProblem<i64>>>; 3] {
+    [
+        Box::new(ExploreOnce(TwoOptNeighborhood)),
+        Box::new(ExploreOnce(SwapNeighborhood)),
+        Box::new(ExploreOnce(InsertsNeighborhood)),
+    ]
+}
+
+#[test]
+fn double_bridge_keeps_the_vertices() {
+    for (seed, symmetric) in (0..4).cartesian_product([true, false]) {
+        let problem = common::random_instance(30, 10, symmetric, seed);
+        let current =
+            RandomSolution::new(SmallRng::seed_from_u64(seed)).make_intial_solution(&problem);
+        let mut perturbation = DoubleBridge::new(SmallRng::seed_from_u64(seed));
+        for _ in 0..20 {
+            let perturbed = perturbation.perturb(&problem, &current);
+            common::assert_valid(&problem, &perturbed);
+            assert_ne!(perturbed.tour(), current.tour());
+            assert_eq!(
+                perturbed.tour().iter().sorted().collect_vec(),
+                current.tour().iter().sorted().collect_vec()
+            );
+        }
+    }
+}
+
+#[test]
+fn restarts_never_return_worse_solutions() {
+    for (seed, symmetric) in (0..4).cartesian_product([true, false]) {
+        let problem = common::random_instance(30, 10, symmetric, seed);
+        let current =
+            RandomSolution::new(SmallRng::seed_from_u64(seed)).make_intial_solution(&problem);
+        let t = || Termination::after_iterations(200);
+
+        let stopped = Cycle::new(heuristics(), t()).improve(&problem, current.clone());
+        common::assert_valid(&problem, &stopped);
+        for on_exhaustion in [
+            OnExhaustion::restart(DoubleBridge::new(SmallRng::seed_from_u64(seed))),
+            OnExhaustion::adaptive_restart(DoubleBridge::new(SmallRng::seed_from_u64(seed))),
+        ] {
+            let restarted = Cycle::with_on_exhaustion(heuristics(), t(), on_exhaustion)
+                .improve(&problem, current.clone());
+            common::assert_valid(&problem, &restarted);
+            assert!(restarted.weight() <= stopped.weight());
+        }
+    }
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use gtsp::{gtsp::DynamicProgramming, MetaHeuristic as _};
+
+#[test]
+fn matches_brute_force() {
+    for seed in 0..10 {
+        for symmetric in [true, false] {
+            let problem = common::random_instance(14, 6, symmetric, seed);
+            let solution = DynamicProgramming::new().run(&problem);
+            common::assert_valid(&problem, &solution);
+            assert_eq!(solution.weight(), common::brute_force(&problem));
+        }
+    }
+}
+
+#[test]
+fn rejects_large_instances() {
+    let problem = common::random_instance(40, 20, true, 0);
+    assert!(DynamicProgramming::new().check(&problem).is_ok());
+    assert!(DynamicProgramming::with_max_memory(1 << 20)
+        .check(&problem)
+        .is_err());
+}
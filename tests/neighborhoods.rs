@@ -0,0 +1,196 @@
+mod common;
+
+use std::collections::{HashMap, HashSet};
+
+use gtsp::{
+    gtsp::{
+        neighborhoods::{
+            Candidates, ClusterSwapNeighborhood, InsertsNeighborhood, Or3OptNeighborhood,
+            OrOptNeighborhood, SwapNeighborhood, ThreeOptNeighborhood, TwoOptNeighborhood,
+        },
+        GtspProblem, RandomSolution,
+    },
+    InitialSolution as _, Move, MoveNeighborhood,
+};
+use itertools::Itertools as _;
+use rand::{rngs::SmallRng, Rng as _, SeedableRng as _};
+
+/// Checks the score increase of every move against the weight of the resulting tour.
+fn check_moves<N: MoveNeighborhood<GtspProblem<i64>>>(problem: &GtspProblem<i64>, seed: u64) {
+    let current = RandomSolution::new(SmallRng::seed_from_u64(seed)).make_intial_solution(problem);
+    for m in N::moves_iter(problem, &current) {
+        let score_increase = m.score_increase();
+        let solution = m.into_solution();
+        common::assert_valid(problem, &solution);
+        assert_eq!(solution.weight(), current.weight() - score_increase);
+    }
+}
+
+fn check_deltas<N: MoveNeighborhood<GtspProblem<i64>>>(symmetric: bool) {
+    for seed in 0..3 {
+        check_moves::<N>(&common::random_instance(30, 10, symmetric, seed), seed);
+    }
+}
+
+/// Like `check_deltas`, on a view of the instance with random penalties on a third of the arcs.
+fn check_penalized_deltas<N: MoveNeighborhood<GtspProblem<i64>>>(symmetric: bool) {
+    for seed in 0..3 {
+        let problem = common::random_instance(30, 10, symmetric, seed);
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut penalties = HashMap::new();
+        for (u, v) in (0..30).tuple_combinations() {
+            if rng.gen_ratio(1, 3) {
+                let penalty = rng.gen_range(1..100);
+                penalties.insert((u, v), penalty);
+                penalties.insert((v, u), if symmetric { penalty } else { 0 });
+            }
+        }
+        let mut view = problem.penalized();
+        for (&(u, v), &penalty) in &penalties {
+            view.add_penalty(u, v, penalty);
+        }
+        for (&(u, v), &penalty) in &penalties {
+            if problem.cluster_of(u) != problem.cluster_of(v) {
+                assert_eq!(view.dist(u, v), problem.dist(u, v) + penalty);
+                assert_eq!(view.base_dist(u, v), problem.dist(u, v));
+            }
+        }
+        check_moves::<N>(&view, seed);
+    }
+}
+
+/// Checks that random scans generate the same moves as `moves_iter`, in a different order.
+fn check_random_order<N: MoveNeighborhood<GtspProblem<i64>>>(symmetric: bool) {
+    let problem = common::random_instance(30, 10, symmetric, 0);
+    let current = RandomSolution::new(SmallRng::seed_from_u64(0)).make_intial_solution(&problem);
+    let sorted_moves = |moves: N::Iter<'_>| {
+        moves
+            .map(|m| (m.score_increase(), m.into_solution().tour().to_owned()))
+            .sorted()
+            .collect_vec()
+    };
+    let first_tour =
+        |mut moves: N::Iter<'_>| moves.next().map(|m| m.into_solution().tour().to_owned());
+    let moves = sorted_moves(N::moves_iter(&problem, &current));
+    let mut first_tours = HashSet::new();
+    for seed in 0..4 {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        assert_eq!(
+            sorted_moves(N::random_moves_iter(&problem, &current, &mut rng)),
+            moves
+        );
+        let mut rng = SmallRng::seed_from_u64(seed);
+        first_tours.insert(first_tour(N::random_moves_iter(
+            &problem, &current, &mut rng,
+        )));
+    }
+    assert!(first_tours.len() > 1);
+}
+
+/// Checks that random moves are moves of `moves_iter`, and that many different ones are drawn.
+fn check_random_moves<N: MoveNeighborhood<GtspProblem<i64>>>(symmetric: bool) {
+    let problem = common::random_instance(30, 10, symmetric, 0);
+    let current = RandomSolution::new(SmallRng::seed_from_u64(0)).make_intial_solution(&problem);
+    let moves = N::moves_iter(&problem, &current)
+        .map(|m| (m.score_increase(), m.into_solution().tour().to_owned()))
+        .collect::<HashSet<_>>();
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut drawn = HashSet::new();
+    for _ in 0..100 {
+        let m = N::random_move(&problem, &current, &mut rng).unwrap();
+        let m = (m.score_increase(), m.into_solution().tour().to_owned());
+        assert!(moves.contains(&m));
+        drawn.insert(m);
+    }
+    assert!(drawn.len() * 2 > moves.len().min(100));
+}
+
+#[test]
+fn two_opt_deltas() {
+    check_deltas::<TwoOptNeighborhood>(true);
+    check_deltas::<TwoOptNeighborhood>(false);
+}
+
+#[test]
+fn swap_deltas() {
+    check_deltas::<SwapNeighborhood>(true);
+    check_deltas::<SwapNeighborhood>(false);
+}
+
+#[test]
+fn cluster_swap_deltas() {
+    check_deltas::<ClusterSwapNeighborhood>(true);
+    check_deltas::<ClusterSwapNeighborhood>(false);
+}
+
+#[test]
+fn inserts_deltas() {
+    check_deltas::<InsertsNeighborhood>(true);
+    check_deltas::<InsertsNeighborhood>(false);
+}
+
+#[test]
+fn or_opt_deltas() {
+    check_deltas::<OrOptNeighborhood>(true);
+    check_deltas::<OrOptNeighborhood>(false);
+}
+
+#[test]
+fn three_opt_deltas() {
+    check_deltas::<ThreeOptNeighborhood>(true);
+    check_deltas::<ThreeOptNeighborhood>(false);
+}
+
+#[test]
+fn or_3opt_deltas() {
+    check_deltas::<Or3OptNeighborhood>(true);
+    check_deltas::<Or3OptNeighborhood>(false);
+}
+
+#[test]
+fn candidate_deltas() {
+    for symmetric in [true, false] {
+        check_deltas::<Candidates<TwoOptNeighborhood>>(symmetric);
+        check_deltas::<Candidates<SwapNeighborhood>>(symmetric);
+        check_deltas::<Candidates<ClusterSwapNeighborhood>>(symmetric);
+        check_deltas::<Candidates<InsertsNeighborhood>>(symmetric);
+        check_deltas::<Candidates<OrOptNeighborhood>>(symmetric);
+    }
+}
+
+#[test]
+fn penalized_deltas() {
+    for symmetric in [true, false] {
+        check_penalized_deltas::<TwoOptNeighborhood>(symmetric);
+        check_penalized_deltas::<SwapNeighborhood>(symmetric);
+        check_penalized_deltas::<InsertsNeighborhood>(symmetric);
+        check_penalized_deltas::<OrOptNeighborhood>(symmetric);
+        check_penalized_deltas::<ThreeOptNeighborhood>(symmetric);
+    }
+}
+
+#[test]
+fn random_order_moves() {
+    for symmetric in [true, false] {
+        check_random_order::<TwoOptNeighborhood>(symmetric);
+        check_random_order::<SwapNeighborhood>(symmetric);
+        check_random_order::<ClusterSwapNeighborhood>(symmetric);
+        check_random_order::<InsertsNeighborhood>(symmetric);
+        check_random_order::<OrOptNeighborhood>(symmetric);
+        check_random_order::<ThreeOptNeighborhood>(symmetric);
+        check_random_order::<Candidates<TwoOptNeighborhood>>(symmetric);
+    }
+}
+
+#[test]
+fn random_moves() {
+    for symmetric in [true, false] {
+        check_random_moves::<TwoOptNeighborhood>(symmetric);
+        check_random_moves::<SwapNeighborhood>(symmetric);
+        check_random_moves::<ClusterSwapNeighborhood>(symmetric);
+        check_random_moves::<InsertsNeighborhood>(symmetric);
+        check_random_moves::<OrOptNeighborhood>(symmetric);
+        check_random_moves::<ThreeOptNeighborhood>(symmetric);
+        check_random_moves::<Candidates<TwoOptNeighborhood>>(symmetric);
+    }
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use gtsp::{
+    gtsp::{DynamicProgramming, NoonBean},
+    MetaHeuristic as _,
+};
+use itertools::Itertools as _;
+
+#[test]
+fn tours_correspond() {
+    for symmetric in [true, false] {
+        let problem = common::random_instance(20, 6, symmetric, 0);
+        let atsp = NoonBean::new(&problem);
+        let optimum = DynamicProgramming::new().run(&problem);
+
+        let tour = atsp.to_tour(&problem, &optimum);
+        let atsp_weight = tour
+            .iter()
+            .circular_tuple_windows()
+            .map(|(&u, &v)| atsp.dist(u, v))
+            .sum::<i64>();
+        assert_eq!(atsp_weight, optimum.weight() + 6 * atsp.big_m());
+
+        let solution = atsp.to_solution(&problem, &tour).unwrap();
+        assert_eq!(solution.weight(), optimum.weight());
+        assert_eq!(
+            solution.tour().iter().sorted().collect_vec(),
+            optimum.tour().iter().sorted().collect_vec()
+        );
+    }
+}
@@ -0,0 +1,191 @@
+mod common;
+
+use std::time::Duration;
+
+use gtsp::{
+    acceptance::{AcceptanceSearch, GreatDeluge, LateAcceptance, ThresholdAccepting},
+    chain::Chain,
+    cycle_neighborhoods::{Cycle, ExploreOnce, OnExhaustion},
+    gtsp::{
+        neighborhoods::{
+            Candidates, ClusterSwapNeighborhood, InsertsNeighborhood, Or3OptNeighborhood,
+            OrOptNeighborhood, SwapNeighborhood, ThreeOptNeighborhood, TwoOptNeighborhood,
+        },
+        Alns, AntColony, CheapestInsertion, ClusterOptimization, DontLookLocalSearch, DoubleBridge,
+        DynamicProgramming, FarthestInsertion, GtspProblem, GuidedLocalSearch, LinKernighan,
+        NearestNeighbor, PathRelinking, RandomSolution, WindowClusterOptimization, WithWindowCo,
+    },
+    localsearch::{LocalSearch, Pivoting},
+    multistart::Multistart,
+    tabusearch::{TabuSearch, Tenure},
+    termination::Termination,
+    ImproveInitial, ImprovementHeuristic, MetaHeuristic,
+};
+use rand::{rngs::SmallRng, SeedableRng as _};
+
+#[test]
+fn heuristics_are_never_better_than_optimum() {
+    for seed in 0..4 {
+        for symmetric in [true, false] {
+            let problem = common::random_instance(24, 8, symmetric, seed);
+            let optimum = DynamicProgramming::new().run(&problem).weight();
+
+            macro_rules! check {
+                ($m: expr) => {
+                    let solution = $m.run(&problem);
+                    common::assert_valid(&problem, &solution);
+                    assert!(solution.weight() >= optimum);
+                };
+            }
+            macro_rules! of_random {
+                ($im: expr) => {
+                    ImproveInitial::new(RandomSolution::new(SmallRng::seed_from_u64(seed)), $im)
+                };
+            }
+            let t = || Termination::after_iterations(50);
+
+            check!(ImproveInitial::new(NearestNeighbor, ClusterOptimization));
+            check!(ImproveInitial::new(CheapestInsertion, ClusterOptimization));
+            check!(ImproveInitial::new(FarthestInsertion, ClusterOptimization));
+            check!(of_random!(LocalSearch::<TwoOptNeighborhood>::new(t())));
+            check!(of_random!(LocalSearch::<SwapNeighborhood>::new(t())));
+            check!(of_random!(LocalSearch::<ClusterSwapNeighborhood>::new(t())));
+            check!(of_random!(TabuSearch::<ClusterSwapNeighborhood>::new(
+                t(),
+                Tenure::fixed(10)
+            )));
+            check!(of_random!(
+                LocalSearch::<TwoOptNeighborhood>::with_pivoting(t(), Pivoting::FirstImprovement)
+            ));
+            check!(of_random!(
+                LocalSearch::<InsertsNeighborhood>::with_pivoting(
+                    t(),
+                    Pivoting::RandomizedFirstImprovement(SmallRng::seed_from_u64(seed))
+                )
+            ));
+            check!(of_random!(Chain::new(
+                LocalSearch::<InsertsNeighborhood>::new(t()),
+                ClusterOptimization
+            )));
+            check!(of_random!(LocalSearch::<OrOptNeighborhood>::new(t())));
+            check!(of_random!(LocalSearch::<ThreeOptNeighborhood>::new(t())));
+            check!(of_random!(
+                LocalSearch::<Candidates<OrOptNeighborhood>>::new(t())
+            ));
+            check!(of_random!(Chain::new(
+                LocalSearch::<SwapNeighborhood>::new(t()),
+                WindowClusterOptimization::new(3)
+            )));
+            check!(of_random!(
+                LocalSearch::<WithWindowCo<TwoOptNeighborhood>>::new(t())
+            ));
+            check!(of_random!(
+                TabuSearch::<WithWindowCo<InsertsNeighborhood, 2>>::new(t(), Tenure::fixed(10))
+            ));
+            check!(of_random!(DontLookLocalSearch::<TwoOptNeighborhood>::new(
+                t()
+            )));
+            check!(of_random!(
+                DontLookLocalSearch::<SwapNeighborhood>::new(t())
+            ));
+            check!(of_random!(DontLookLocalSearch::<InsertsNeighborhood>::new(
+                t()
+            )));
+            check!(of_random!(
+                TabuSearch::<Candidates<TwoOptNeighborhood>>::new(t(), Tenure::fixed(10))
+            ));
+            check!(of_random!(TabuSearch::<Or3OptNeighborhood>::new(
+                t(),
+                Tenure::fixed(10)
+            )));
+            check!(of_random!(TabuSearch::<OrOptNeighborhood>::new(
+                t(),
+                Tenure::fixed(10)
+            )));
+            check!(of_random!(TabuSearch::<TwoOptNeighborhood>::new(
+                t(),
+                Tenure::fixed(10)
+            )));
+            check!(of_random!(TabuSearch::<InsertsNeighborhood>::new(
+                t(),
+                Tenure::randomized(5, 15, SmallRng::seed_from_u64(seed))
+            )));
+            check!(of_random!(TabuSearch::<SwapNeighborhood>::new(
+                t(),
+                Tenure::reactive(5, 50)
+            )));
+            check!(AntColony::new(t(), SmallRng::seed_from_u64(seed)));
+            check!(AntColony::with_local_search(
+                t(),
+                SmallRng::seed_from_u64(seed),
+                LocalSearch::<TwoOptNeighborhood>::new(Termination::never())
+            ));
+            check!(of_random!(GuidedLocalSearch::new(
+                t(),
+                LocalSearch::<TwoOptNeighborhood>::new(t())
+            )));
+            check!(of_random!(GuidedLocalSearch::new(
+                t(),
+                LocalSearch::<InsertsNeighborhood>::new(t())
+            )));
+            check!(of_random!(AcceptanceSearch::<SwapNeighborhood, _, _>::new(
+                t(),
+                LateAcceptance::new(20),
+                SmallRng::seed_from_u64(seed)
+            )));
+            check!(of_random!(
+                AcceptanceSearch::<InsertsNeighborhood, _, _>::new(
+                    t(),
+                    ThresholdAccepting::new(0.01, 0.99),
+                    SmallRng::seed_from_u64(seed)
+                )
+            ));
+            check!(of_random!(
+                AcceptanceSearch::<TwoOptNeighborhood, _, _>::new(
+                    t(),
+                    GreatDeluge::new(1e-3),
+                    SmallRng::seed_from_u64(seed)
+                )
+            ));
+            check!(of_random!(LinKernighan::new(t())));
+            check!(of_random!(Alns::new(t(), SmallRng::seed_from_u64(seed))));
+            check!(of_random!(Alns::with_max_removed(
+                t(),
+                SmallRng::seed_from_u64(seed),
+                3
+            )));
+            check!(of_random!(Chain::new(
+                LinKernighan::with_max_depth(t(), 5),
+                ClusterOptimization
+            )));
+            check!(of_random!(Cycle::new(
+                [
+                    Box::new(ExploreOnce(TwoOptNeighborhood))
+                        as Box<dyn ImprovementHeuristic<GtspProblem<_>>>,
+                    Box::new(ExploreOnce(SwapNeighborhood)),
+                    Box::new(ExploreOnce(InsertsNeighborhood)),
+                ],
+                t()
+            )));
+            check!(of_random!(Cycle::with_on_exhaustion(
+                [
+                    Box::new(ExploreOnce(TwoOptNeighborhood))
+                        as Box<dyn ImprovementHeuristic<GtspProblem<_>>>,
+                    Box::new(ExploreOnce(SwapNeighborhood)),
+                    Box::new(ExploreOnce(InsertsNeighborhood)),
+                ],
+                t(),
+                OnExhaustion::adaptive_restart(DoubleBridge::new(SmallRng::seed_from_u64(seed)))
+            )));
+            check!(Multistart::with_post_processing(
+                Termination::after_duration(Duration::from_millis(50)),
+                || of_random!(LocalSearch::<TwoOptNeighborhood>::new(t())),
+                PathRelinking::new(t(), LocalSearch::<SwapNeighborhood>::new(t()))
+            ));
+            check!(Multistart::new(
+                Termination::after_duration(Duration::from_millis(50)),
+                || { of_random!(LocalSearch::<TwoOptNeighborhood>::new(t())) }
+            ));
+        }
+    }
+}
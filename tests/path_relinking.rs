@@ -0,0 +1,128 @@
+mod common;
+
+use std::collections::HashSet;
+
+use gtsp::{
+    gtsp::{
+        neighborhoods::TwoOptNeighborhood, path_relinking::path, GtspProblem, PathRelinking,
+        RandomSolution, Solution,
+    },
+    localsearch::LocalSearch,
+    multistart::PostProcessing,
+    termination::Termination,
+    ImprovementHeuristic as _, InitialSolution as _,
+};
+use itertools::Itertools as _;
+use rand::{rngs::SmallRng, SeedableRng as _};
+
+/// The undirected edges of the tour, which identify it up to rotation and direction.
+fn edges(solution: &Solution<i64>) -> HashSet<(usize, usize)> {
+    solution
+        .tour()
+        .iter()
+        .circular_tuple_windows()
+        .map(|(&u, &v)| (u.min(v), u.max(v)))
+        .collect()
+}
+
+fn instances() -> impl Iterator<Item = GtspProblem<i64>> {
+    (0..4).map(|seed| common::random_instance(40, 12, true, seed))
+}
+
+#[test]
+fn elite_pool_is_diverse_and_keeps_the_best() {
+    for (seed, problem) in instances().enumerate() {
+        let mut initial = RandomSolution::new(SmallRng::seed_from_u64(seed as u64));
+        let mut path_relinking = PathRelinking::with_pool_size(
+            Termination::never(),
+            LocalSearch::<TwoOptNeighborhood>::new(Termination::never()),
+            5,
+        );
+
+        let mut best = None::<Solution<i64>>;
+        for _ in 0..50 {
+            let solution = initial.make_intial_solution(&problem);
+            path_relinking.add_solution(&problem, &solution);
+            path_relinking.add_solution(&problem, &solution);
+            if best
+                .as_ref()
+                .map_or(true, |b| solution.weight() < b.weight())
+            {
+                best = Some(solution);
+            }
+
+            let elite = path_relinking.elite();
+            assert!(elite.len() <= 5);
+            assert!(elite
+                .iter()
+                .tuple_combinations()
+                .all(|(a, b)| edges(a) != edges(b)));
+            assert!(elite.contains(best.as_ref().unwrap()));
+        }
+    }
+}
+
+#[test]
+fn relinking_never_worsens_the_best_solution() {
+    for (seed, problem) in instances().enumerate() {
+        let mut initial = RandomSolution::new(SmallRng::seed_from_u64(seed as u64));
+        let mut local_search = LocalSearch::<TwoOptNeighborhood>::new(Termination::never());
+        let mut path_relinking = PathRelinking::new(
+            Termination::after_iterations(20),
+            LocalSearch::<TwoOptNeighborhood>::new(Termination::never()),
+        );
+
+        let solutions = (0..10)
+            .map(|_| local_search.improve(&problem, initial.make_intial_solution(&problem)))
+            .collect_vec();
+        for solution in &solutions {
+            path_relinking.add_solution(&problem, solution);
+        }
+        let best = solutions.into_iter().min_by_key(|s| s.weight()).unwrap();
+        let result = path_relinking.process(&problem, best.clone());
+        common::assert_valid(&problem, &result);
+        assert!(result.weight() <= best.weight());
+    }
+}
+
+#[test]
+fn path_reaches_the_guiding_solution() {
+    for seed in 0..4 {
+        for symmetric in [true, false] {
+            let problem = common::random_instance(40, 12, symmetric, seed);
+            let mut initial = RandomSolution::new(SmallRng::seed_from_u64(seed));
+            for _ in 0..10 {
+                let initiating = initial.make_intial_solution(&problem);
+                let guiding = initial.make_intial_solution(&problem);
+                let path = path(&problem, &initiating, &guiding);
+
+                assert!(path.len() <= 12);
+                for solution in &path {
+                    common::assert_valid(&problem, solution);
+                }
+                assert_eq!(path.last().unwrap().tour(), guiding.tour());
+            }
+        }
+    }
+}
+
+#[test]
+fn path_follows_the_orientation_of_the_guiding_solution() {
+    for (seed, problem) in instances().enumerate() {
+        let mut initial = RandomSolution::new(SmallRng::seed_from_u64(seed as u64));
+        let initiating = initial.make_intial_solution(&problem);
+        let mut tour = initiating.tour().to_owned();
+        tour.reverse();
+        tour.rotate_left(5);
+        let reversed = Solution::new(&problem, tour.clone());
+        assert!(path(&problem, &initiating, &reversed).is_empty());
+
+        // Relinking to the reversed tour with one other vertex takes a single step.
+        let cluster = &problem.clusters()[problem.cluster_of(tour[3])];
+        tour[3] = *cluster.iter().find(|&&v| v != tour[3]).unwrap();
+        let guiding = Solution::new(&problem, tour);
+        let path = path(&problem, &initiating, &guiding);
+        assert_eq!(path.len(), 1);
+        assert_eq!(path[0].tour(), guiding.tour());
+    }
+}